@@ -0,0 +1,154 @@
+//! Measures how long compiling embedded data takes, and how much memory rustc needs for it, comparing the decimal
+//! array the macros used to expand to with the byte string literal they expand to now.
+//!
+//! Run with `cargo bench --bench expansion`. The input size defaults to 50 MB, and can be changed by setting
+//! `INCLUDE_ZSTD_BENCH_MB`. Peak memory is only reported on Unix.
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use std::{
+    env,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::Instant,
+};
+
+/// Compression level used throughout, low so that the decimal array case doesn't get an unfair head start.
+const LEVEL: i32 = 1;
+
+fn main() {
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--measure" {
+            return measure(Path::new(&args.next().unwrap()));
+        }
+    }
+
+    let size = env::var("INCLUDE_ZSTD_BENCH_MB").map_or(50, |size| size.parse().unwrap()) << 20;
+    let data = generate(size);
+    let compressed = zstd::encode_all(&data[..], LEVEL).unwrap();
+    println!(
+        "input: {} MB, compressed at level {LEVEL}: {:.1} MB",
+        size >> 20,
+        compressed.len() as f64 / f64::from(1 << 20),
+    );
+
+    let decimal_array = compressed
+        .iter()
+        .map(|byte| byte.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    let byte_string = compressed.iter().fold(String::new(), |mut literal, byte| {
+        let _ = write!(literal, "\\x{byte:02x}");
+        literal
+    });
+    let cases = [
+        ("decimal array", format!("[{decimal_array}]")),
+        ("byte string", format!("*b\"{byte_string}\"")),
+        (
+            "include_zstd!",
+            format!("include_zstd::include_zstd!(\"asset.bin\", {LEVEL})"),
+        ),
+    ];
+    for (name, expr) in cases {
+        let dir = fixture(name, &data);
+        common::write(
+            &dir,
+            "src/main.rs",
+            &format!("fn main() {{ ::std::hint::black_box({expr}); }}\n"),
+        );
+
+        let output = Command::new(env::current_exe().unwrap())
+            .arg("--measure")
+            .arg(&dir)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        println!("{name:>14}: {}", String::from_utf8_lossy(&output.stdout));
+    }
+}
+
+/// Creates a fixture crate holding `data` in `asset.bin`, with its dependencies already built.
+fn fixture(name: &str, data: &[u8]) -> PathBuf {
+    let name = format!(
+        "bench-{}",
+        name.replace(|c: char| !c.is_alphanumeric(), "-")
+    );
+    let dir = common::fixture(&name, "", &[("src/main.rs", "fn main() {}\n")]);
+    fs::write(dir.join("asset.bin"), data).unwrap();
+    common::cargo(&dir, &["build", "--quiet"], &[]);
+
+    dir
+}
+
+/// Builds the fixture crate in `dir` and prints the time taken, along with the peak memory of the processes involved.
+/// This runs in its own process, so that the peak memory of the other cases doesn't carry over.
+fn measure(dir: &Path) {
+    let start = Instant::now();
+    // The cache would skip the compression on every run but the first
+    common::cargo(
+        dir,
+        &["build", "--quiet"],
+        &[("INCLUDE_ZSTD_CACHE_DIR", "")],
+    );
+    print!("{:>8.2} s", start.elapsed().as_secs_f64());
+
+    #[cfg(unix)]
+    {
+        let mut usage = unsafe { std::mem::zeroed::<libc::rusage>() };
+        assert_eq!(
+            unsafe { libc::getrusage(libc::RUSAGE_CHILDREN, &mut usage) },
+            0
+        );
+        // Reported in kilobytes on Linux, and in bytes on macOS
+        let max_rss = if cfg!(target_os = "macos") {
+            usage.ru_maxrss >> 20
+        } else {
+            usage.ru_maxrss >> 10
+        };
+        print!("{max_rss:>8} MB peak");
+    }
+}
+
+/// Generates `size` bytes of text, compressible about as well as typical assets.
+fn generate(size: usize) -> Vec<u8> {
+    const WORDS: &[&str] = &[
+        "zstd",
+        "include",
+        "compressed",
+        "asset",
+        "frame",
+        "window",
+        "block",
+        "literal",
+        "sequence",
+        "dictionary",
+        "entropy",
+        "huffman",
+        "offset",
+        "match",
+        "length",
+        "static",
+    ];
+
+    let mut data = Vec::with_capacity(size);
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    while data.len() < size {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        data.extend_from_slice(WORDS[state as usize % WORDS.len()].as_bytes());
+        // Numbers keep the text from compressing unrealistically well
+        data.extend_from_slice(format!(" {} ", state % 1000).as_bytes());
+    }
+    data.truncate(size);
+
+    data
+}
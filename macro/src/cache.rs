@@ -0,0 +1,127 @@
+use std::{env, ffi::OsString, fs, path::PathBuf, process};
+
+use sha2::{Digest, Sha256};
+
+use crate::options::CompressOptions;
+
+/// Environment variable overriding the cache directory, or disabling the cache when empty.
+const CACHE_DIR_ENV: &str = "INCLUDE_ZSTD_CACHE_DIR";
+
+/// Content-addressed store of compressed output, so that unchanged files are not recompressed on every expansion.
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    /// Opens the cache in `INCLUDE_ZSTD_CACHE_DIR`, or next to rustc's output directory. Returns `None` when neither
+    /// is available, in which case every file is compressed again.
+    pub fn open() -> Option<Self> {
+        let dir = match env::var_os(CACHE_DIR_ENV) {
+            Some(dir) if dir.is_empty() => return None,
+            Some(dir) => PathBuf::from(dir),
+            None => out_dir()?.parent()?.join("include-zstd-cache"),
+        };
+
+        Some(Self { dir })
+    }
+
+    /// Returns the key identifying the output of compressing `bytes` with `options`.
+    pub fn key(bytes: &[u8], options: &CompressOptions) -> String {
+        let mut hasher = Self::hasher(&options.encoder_params());
+        if let Some(dict) = &options.dict {
+            hasher.update(&dict.bytes);
+        }
+        hasher.update(bytes);
+
+        hex(hasher)
+    }
+
+    /// Returns the key identifying the dictionary of at most `size` bytes trained on `samples`.
+    pub fn dictionary_key(samples: &[Vec<u8>], size: usize) -> String {
+        let mut hasher = Self::hasher(&format!("dictionary size={size}"));
+        // Lengths keep samples from running into each other
+        for sample in samples {
+            hasher.update((sample.len() as u64).to_le_bytes());
+            hasher.update(sample);
+        }
+
+        hex(hasher)
+    }
+
+    fn hasher(params: &str) -> Sha256 {
+        let mut hasher = Sha256::new();
+        // The encoder version is part of the key, as its output may change between releases
+        hasher.update(format!(
+            "{} {} zstd {} {params}\n",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION"),
+            zstd::zstd_safe::version_number(),
+        ));
+
+        hasher
+    }
+
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        fs::read(self.path(key)).ok()
+    }
+
+    /// Stores `compressed_bytes` under `key`. Failures are ignored, as they only cost a recompression later.
+    pub fn insert(&self, key: &str, compressed_bytes: &[u8]) {
+        // Writing to a temporary file first keeps concurrent compilations from reading a partial entry
+        let temp_path = self.dir.join(format!("{key}.{}.tmp", process::id()));
+        let written = fs::create_dir_all(&self.dir)
+            .and_then(|()| fs::write(&temp_path, compressed_bytes))
+            .and_then(|()| fs::rename(&temp_path, self.path(key)));
+        if written.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.zst"))
+    }
+}
+
+fn hex(hasher: Sha256) -> String {
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Returns the `--out-dir` passed to rustc, which the macro runs inside of. Cargo points it at the `deps` directory
+/// of the current profile within the target directory.
+fn out_dir() -> Option<PathBuf> {
+    rustc_arg("--out-dir").map(PathBuf::from)
+}
+
+/// Returns cargo's target directory, which holds `<profile>/deps`, or `<triple>/<profile>/deps` when building for an
+/// explicit `--target`.
+pub fn target_dir() -> Option<PathBuf> {
+    let profile_dir = out_dir()?.parent()?.to_path_buf();
+    let target_dir = match rustc_arg("--target") {
+        Some(_) => profile_dir.parent()?.parent()?,
+        None => profile_dir.parent()?,
+    };
+
+    Some(target_dir.to_path_buf())
+}
+
+/// Returns the value of the `name` argument passed to rustc, given either as a separate argument or after `=`.
+fn rustc_arg(name: &str) -> Option<OsString> {
+    let mut args = env::args_os();
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return args.next();
+        }
+        if let Some(value) = arg
+            .to_str()
+            .and_then(|arg| arg.strip_prefix(name)?.strip_prefix('='))
+        {
+            return Some(OsString::from(value));
+        }
+    }
+
+    None
+}
@@ -0,0 +1,154 @@
+use std::{fs, path::PathBuf};
+
+use globset::GlobBuilder;
+use syn::{LitInt, LitStr};
+
+use crate::{cache::Cache, manifest_dir, options::MacroInput, read, relative_path, walk_dir};
+
+/// Magic number opening dictionaries in the format produced by `zstd --train`.
+const MAGIC: [u8; 4] = 0xEC30_A437_u32.to_le_bytes();
+
+/// Size of trained dictionaries when none is given, the default of `zstd --train`.
+const DEFAULT_SIZE: usize = 110 << 10;
+/// Smallest dictionary Zstd can train.
+const MIN_SIZE: usize = 256;
+
+/// Zstd dictionary that data is compressed with.
+pub struct Dictionary {
+    pub bytes: Vec<u8>,
+    pub id: u32,
+}
+
+impl Dictionary {
+    /// Loads the dictionary at `path`, relative to the invoking crate, returning it along with its resolved path.
+    pub fn load(input: &MacroInput, path: &LitStr) -> syn::Result<(Self, PathBuf)> {
+        let resolved = crate::resolve(input, &path.value())?;
+        let bytes = fs::read(&resolved).map_err(|err| {
+            syn::Error::new_spanned(
+                path,
+                format!("failed to read `{}`: {err}", resolved.display()),
+            )
+        })?;
+
+        let dict = Self::from_bytes(bytes).map_err(|err| {
+            syn::Error::new_spanned(
+                path,
+                format!("`{}` is not a usable dictionary: {err}", resolved.display()),
+            )
+        })?;
+        Ok((dict, resolved))
+    }
+
+    /// Trains a dictionary of at most `size` bytes on the files matching the `samples` glob, relative to the invoking
+    /// crate, returning it along with the paths of the samples. Training on the same samples yields the same
+    /// dictionary, which is cached like compressed output.
+    pub fn train(
+        input: &MacroInput,
+        samples: &LitStr,
+        size: Option<&LitInt>,
+    ) -> syn::Result<(Self, Vec<PathBuf>)> {
+        let size = match size {
+            Some(lit) => {
+                let size = lit.base10_parse()?;
+                if size < MIN_SIZE {
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        format!(
+                            "dictionary size of {size} is too small, expected at least {MIN_SIZE}"
+                        ),
+                    ));
+                }
+                size
+            }
+            None => DEFAULT_SIZE,
+        };
+
+        let paths = sample_paths(input, samples)?;
+        if paths.is_empty() {
+            return Err(syn::Error::new_spanned(
+                samples,
+                format!("no files match `{}`", samples.value()),
+            ));
+        }
+        let samples_bytes = paths
+            .iter()
+            .map(|path| read(input, path))
+            .collect::<syn::Result<Vec<_>>>()?;
+
+        let cache = Cache::open();
+        let key = Cache::dictionary_key(&samples_bytes, size);
+        let bytes = match cache.as_ref().and_then(|cache| cache.get(&key)) {
+            Some(bytes) => bytes,
+            None => {
+                let bytes = zstd::dict::from_samples(&samples_bytes, size).map_err(|err| {
+                    syn::Error::new_spanned(
+                        samples,
+                        format!(
+                            "failed to train a dictionary on the {} files matching `{}`: {err}",
+                            paths.len(),
+                            samples.value()
+                        ),
+                    )
+                })?;
+                if let Some(cache) = cache {
+                    cache.insert(&key, &bytes);
+                }
+                bytes
+            }
+        };
+
+        let dict = Self::from_bytes(bytes).map_err(|err| {
+            syn::Error::new_spanned(samples, format!("trained dictionary is not usable: {err}"))
+        })?;
+        Ok((dict, paths))
+    }
+
+    /// Checks that `bytes` hold a dictionary the runtime decoder can use, returning an error message otherwise.
+    fn from_bytes(bytes: Vec<u8>) -> Result<Self, String> {
+        // The decoder only supports dictionaries with a header, not raw content used as a prefix
+        if bytes.get(..4) != Some(&MAGIC) {
+            return Err(String::from(
+                "it does not start with the dictionary magic number, raw content dictionaries are not supported",
+            ));
+        }
+        let id = bytes
+            .get(4..8)
+            .map(|id| u32::from_le_bytes(id.try_into().unwrap()))
+            .filter(|&id| id != 0)
+            .ok_or_else(|| String::from("its dictionary ID is missing or zero"))?;
+
+        Ok(Self { bytes, id })
+    }
+}
+
+/// Returns the files matching the `samples` glob, sorted so that training is reproducible.
+fn sample_paths(input: &MacroInput, samples: &LitStr) -> syn::Result<Vec<PathBuf>> {
+    let pattern = samples.value();
+    let glob = GlobBuilder::new(&pattern)
+        .literal_separator(true)
+        .build()
+        .map_err(|err| syn::Error::new_spanned(samples, format!("invalid glob: {err}")))?
+        .compile_matcher();
+
+    // Only the directory the glob can match below is walked, rather than the whole crate
+    let manifest_dir = manifest_dir(input)?;
+    let components: Vec<_> = pattern.split('/').collect();
+    let root = components[..components.len() - 1]
+        .iter()
+        .take_while(|component| !component.contains(['*', '?', '[', '{', '\\']))
+        .fold(manifest_dir.clone(), |root, component| root.join(component));
+    if !root.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = Vec::new();
+    // Samples are picked by glob, so that `**` does not pull in version control or editor files
+    for path in walk_dir(input, &root, false, true)? {
+        if glob.is_match(relative_path(input, &manifest_dir, &path)?) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    Ok(paths)
+}
@@ -1,29 +1,38 @@
-use std::{env, fs, io::Write, path::PathBuf};
+use std::{
+    env, fs,
+    io::Write,
+    path::{Path, PathBuf},
+    str,
+};
 
-use proc_macro::TokenStream;
+use ignore::WalkBuilder;
+use proc_macro::{Group, Literal, TokenStream, TokenTree};
 use proc_macro_crate::FoundCrate;
-use syn::{
-    parse::{Parse, ParseStream},
-    parse_macro_input, LitInt, LitStr, Token,
-};
+use sha2::{Digest, Sha256};
+use syn::parse_macro_input;
 
-struct MacroInput {
-    path: LitStr,
-    _comma: Token![,],
-    compression_level: LitInt,
-}
+mod cache;
+mod dict;
+mod options;
 
-impl Parse for MacroInput {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
-        let path = input.parse()?;
-        let _comma = input.parse()?;
-        let compression_level = input.parse()?;
+use cache::Cache;
+use options::{CompressOptions, DictOptions, DirOptions, MacroInput, MAX_WINDOW_SIZE};
 
-        Ok(Self {
-            path,
-            _comma,
-            compression_level,
-        })
+/// Kind of value produced by the expansion.
+#[derive(Clone, Copy)]
+enum Embed {
+    /// Arbitrary bytes, embedded as an `EmbeddedZstd`.
+    Bytes,
+    /// UTF-8 text, validated at compile time and embedded as an `EmbeddedZstdStr`.
+    Str,
+}
+
+impl Embed {
+    fn type_name(self) -> &'static str {
+        match self {
+            Self::Bytes => "EmbeddedZstd",
+            Self::Str => "EmbeddedZstdStr",
+        }
     }
 }
 
@@ -32,40 +41,440 @@ impl Parse for MacroInput {
 pub fn include_zstd_inner(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as MacroInput);
 
-    let path = PathBuf::from(input.path.value());
-    let compression_level = input.compression_level.base10_parse().unwrap();
+    expand(input, Embed::Bytes).unwrap_or_else(|err| err.to_compile_error().into())
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn include_zstd_str_inner(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as MacroInput);
+
+    expand(input, Embed::Str).unwrap_or_else(|err| err.to_compile_error().into())
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn include_zstd_dir_inner(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as MacroInput);
+
+    expand_dir(input).unwrap_or_else(|err| err.to_compile_error().into())
+}
+
+#[proc_macro]
+#[doc(hidden)]
+pub fn include_zstd_dictionary_inner(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as MacroInput);
+
+    expand_dictionary(input).unwrap_or_else(|err| err.to_compile_error().into())
+}
+
+fn expand(input: MacroInput, embed: Embed) -> syn::Result<TokenStream> {
+    let options = CompressOptions::parse(&input)?;
+    let path = resolve_path(&input)?;
+
+    let bytes = read(&input, &path)?;
+    if let Embed::Str = embed {
+        str::from_utf8(&bytes).map_err(|err| {
+            syn::Error::new_spanned(
+                &input.path,
+                format!("`{}` is not valid UTF-8: {err}", path.display()),
+            )
+        })?;
+    }
+
+    let compressed_bytes = compress(&input, &path, &bytes, &options)?;
+
+    let compressed_bytes_len = compressed_bytes.len();
+    let decompressed_bytes_len = bytes.len();
+
+    let crate_name = crate_name(&input)?;
+    let tracked_path = tracked_path(&input, &path)? + &options.tracked;
+    let type_name = embed.type_name();
+    let mut blobs = Blobs::default();
+    let content_hash = blobs.insert(content_hash(&bytes));
+    let compressed_bytes = blobs.insert(compressed_bytes);
+
+    emit(
+        &input,
+        format!(
+            r#"{{ {tracked_path} unsafe {{ {crate_name}::{type_name}::<{compressed_bytes_len}>::new_unchecked(*{compressed_bytes}, {decompressed_bytes_len}, *{content_hash}) }} }}"#,
+        ),
+        &blobs,
+    )
+}
+
+fn expand_dir(input: MacroInput) -> syn::Result<TokenStream> {
+    let options = DirOptions::parse(&input)?;
+    let root = resolve_path(&input)?;
+
+    let mut entries = Vec::new();
+    for path in walk_dir(&input, &root, options.gitignore, false)? {
+        let relative_path = relative_path(&input, &root, &path)?;
+        if options.is_match(&relative_path) {
+            entries.push((relative_path, path));
+        }
+    }
+    // Entries are looked up with a binary search, and a stable order keeps the output reproducible
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let crate_name = crate_name(&input)?;
+
+    if options.solid {
+        return expand_archive(&input, &crate_name, &root, &entries, &options.compress);
+    }
+
+    let mut tracked_paths = options.compress.tracked.clone();
+    let mut entry_tokens = Vec::with_capacity(entries.len());
+    let mut blobs = Blobs::default();
+    for (relative_path, path) in &entries {
+        let bytes = read(&input, path)?;
+        let compressed_bytes = compress(&input, path, &bytes, &options.compress)?;
+
+        let decompressed_bytes_len = bytes.len();
+        let content_hash = blobs.insert(content_hash(&bytes));
+        let compressed_bytes = blobs.insert(compressed_bytes);
+
+        tracked_paths += &tracked_path(&input, path)?;
+        entry_tokens.push(format!(
+            r#"({relative_path:?}, unsafe {{ {crate_name}::EmbeddedZstdSlice::new_unchecked({compressed_bytes}, {decompressed_bytes_len}, {content_hash}) }})"#,
+        ));
+    }
+    let entry_tokens = entry_tokens.join(",");
+
+    emit(
+        &input,
+        format!(
+            r#"{{ {tracked_paths} const DIR: {crate_name}::EmbeddedZstdDir = unsafe {{ {crate_name}::EmbeddedZstdDir::new_unchecked(&[{entry_tokens}]) }}; DIR }}"#,
+        ),
+        &blobs,
+    )
+}
+
+/// Expands `include_zstd_dir!` in solid mode, compressing every file into a single frame.
+fn expand_archive(
+    input: &MacroInput,
+    crate_name: &str,
+    root: &Path,
+    entries: &[(String, PathBuf)],
+    options: &CompressOptions,
+) -> syn::Result<TokenStream> {
+    let mut tracked_paths = options.tracked.clone();
+    let mut entry_tokens = Vec::with_capacity(entries.len());
+    let mut bytes = Vec::new();
+    let mut separate_size = 0;
+    for (relative_path, path) in entries {
+        let file_bytes = read(input, path)?;
+        // Compressing each file on its own lets the archive report how much the single frame saves
+        separate_size += compress(input, path, &file_bytes, options)?.len();
+
+        let start = bytes.len();
+        bytes.extend_from_slice(&file_bytes);
+        let end = bytes.len();
+
+        tracked_paths += &tracked_path(input, path)?;
+        entry_tokens.push(format!("({relative_path:?}, {start}..{end})"));
+    }
+    let entry_tokens = entry_tokens.join(",");
+
+    let compressed_bytes = compress(input, root, &bytes, options)?;
+    let decompressed_bytes_len = bytes.len();
+    let mut blobs = Blobs::default();
+    let content_hash = blobs.insert(content_hash(&bytes));
+    let compressed_bytes = blobs.insert(compressed_bytes);
+
+    emit(
+        input,
+        format!(
+            r#"{{ {tracked_paths} const ARCHIVE: {crate_name}::EmbeddedZstdArchive = unsafe {{ {crate_name}::EmbeddedZstdArchive::new_unchecked({crate_name}::EmbeddedZstdSlice::new_unchecked({compressed_bytes}, {decompressed_bytes_len}, {content_hash}), &[{entry_tokens}], {separate_size}) }}; ARCHIVE }}"#,
+        ),
+        &blobs,
+    )
+}
+
+fn expand_dictionary(input: MacroInput) -> syn::Result<TokenStream> {
+    let options = DictOptions::parse(&input)?;
+
+    let crate_name = crate_name(&input)?;
+    let id = options.dict.id;
+    let mut blobs = Blobs::default();
+    let dict_bytes = blobs.insert(options.dict.bytes);
 
-    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
-    let path = if path.is_relative() {
-        manifest_dir.join(path)
-    } else {
-        path
+    emit(
+        &input,
+        format!(
+            r#"{{ {} const DICT: {crate_name}::EmbeddedZstdDict = unsafe {{ {crate_name}::EmbeddedZstdDict::new_unchecked({dict_bytes}, {id}) }}; DICT }}"#,
+            options.tracked,
+        ),
+        &blobs,
+    )
+}
+
+fn resolve_path(input: &MacroInput) -> syn::Result<PathBuf> {
+    let path = input.path.as_ref().ok_or_else(|| {
+        syn::Error::new_spanned(
+            input.options.first().map(|option| &option.key),
+            "expected a path before the options",
+        )
+    })?;
+
+    resolve(input, &path.value())
+}
+
+/// Resolves `path` relative to the directory of the invoking crate, unless it is absolute.
+fn resolve(input: &MacroInput, path: &str) -> syn::Result<PathBuf> {
+    let path = PathBuf::from(path);
+    if path.is_absolute() {
+        return Ok(path);
+    }
+
+    Ok(manifest_dir(input)?.join(path))
+}
+
+/// Returns the directory holding the `Cargo.toml` of the crate invoking the macro.
+fn manifest_dir(input: &MacroInput) -> syn::Result<PathBuf> {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").map_err(|err| {
+        syn::Error::new_spanned(
+            &input.path,
+            format!("cannot locate the invoking crate, `CARGO_MANIFEST_DIR` is unavailable: {err}"),
+        )
+    })?;
+
+    Ok(PathBuf::from(manifest_dir))
+}
+
+fn read(input: &MacroInput, path: &Path) -> syn::Result<Vec<u8>> {
+    fs::read(path).map_err(|err| {
+        syn::Error::new_spanned(
+            &input.path,
+            format!("failed to read `{}`: {err}", path.display()),
+        )
+    })
+}
+
+/// Collects every file below `dir`, following symbolic links and optionally skipping files matched by `.gitignore` and
+/// hidden directories such as `.git`.
+///
+/// Cargo's target directory is always skipped, as its contents change on every build and would make the invoking crate
+/// rebuild each time.
+fn walk_dir(
+    input: &MacroInput,
+    dir: &Path,
+    gitignore: bool,
+    skip_hidden: bool,
+) -> syn::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    // Compared canonically, as the target directory and the walked paths may be spelled differently
+    let target_dir = cache::target_dir().and_then(|target_dir| fs::canonicalize(target_dir).ok());
+    let is_skipped = move |entry: &ignore::DirEntry| {
+        entry.depth() > 0
+            && entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_dir())
+            && (skip_hidden && entry.file_name().to_string_lossy().starts_with('.')
+                || target_dir.as_ref().is_some_and(|target_dir| {
+                    fs::canonicalize(entry.path()).is_ok_and(|path| path == *target_dir)
+                }))
+    };
+
+    let walk = WalkBuilder::new(dir)
+        .standard_filters(false)
+        .parents(gitignore)
+        .git_ignore(gitignore)
+        .git_exclude(gitignore)
+        .require_git(false)
+        .follow_links(true)
+        .filter_entry(move |entry| !is_skipped(entry))
+        .build();
+    for entry in walk {
+        let entry = entry.map_err(|err| {
+            syn::Error::new_spanned(
+                &input.path,
+                format!("failed to read directory `{}`: {err}", dir.display()),
+            )
+        })?;
+
+        if entry
+            .file_type()
+            .is_some_and(|file_type| !file_type.is_dir())
+        {
+            files.push(entry.into_path());
+        }
+    }
+
+    Ok(files)
+}
+
+/// Returns `path` relative to `root`, with `/` as the separator on every platform.
+fn relative_path(input: &MacroInput, root: &Path, path: &Path) -> syn::Result<String> {
+    let components = path
+        .strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|component| {
+            component.as_os_str().to_str().ok_or_else(|| {
+                syn::Error::new_spanned(
+                    &input.path,
+                    format!("path `{}` is not valid UTF-8", path.display()),
+                )
+            })
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    Ok(components.join("/"))
+}
+
+fn compress(
+    input: &MacroInput,
+    path: &Path,
+    bytes: &[u8],
+    options: &CompressOptions,
+) -> syn::Result<Vec<u8>> {
+    let cache = Cache::open();
+    let key = Cache::key(bytes, options);
+    if let Some(compressed_bytes) = cache.as_ref().and_then(|cache| cache.get(&key)) {
+        return Ok(compressed_bytes);
+    }
+
+    let compress_error = |err: std::io::Error| {
+        syn::Error::new_spanned(
+            &input.path,
+            format!("failed to compress `{}`: {err}", path.display()),
+        )
     };
 
-    let bytes = fs::read(path).unwrap();
     let mut compressed_bytes = Vec::new();
-    let mut encoder = zstd::Encoder::new(&mut compressed_bytes, compression_level).unwrap();
-    encoder.write_all(&bytes).unwrap();
-    encoder.finish().unwrap();
+    let mut encoder = match &options.dict {
+        Some(dict) => {
+            zstd::Encoder::with_dictionary(&mut compressed_bytes, options.level, &dict.bytes)
+        }
+        None => zstd::Encoder::new(&mut compressed_bytes, options.level),
+    }
+    .map_err(compress_error)?;
+    encoder.include_contentsize(true).map_err(compress_error)?;
+    for &param in &options.params {
+        encoder.set_parameter(param).map_err(compress_error)?;
+    }
+    encoder
+        .set_pledged_src_size(Some(bytes.len() as u64))
+        .map_err(compress_error)?;
+    encoder.write_all(bytes).map_err(compress_error)?;
+    encoder.finish().map_err(compress_error)?;
 
-    let compressed_bytes_len = compressed_bytes.len();
+    let window_size = window_size(&compressed_bytes, bytes.len());
+    if window_size > MAX_WINDOW_SIZE {
+        return Err(syn::Error::new_spanned(
+            &input.path,
+            format!(
+                "compressing `{}` needs a {} MiB window, but the decoder supports at most {} MiB, set a lower \
+                 `window_log`",
+                path.display(),
+                window_size >> 20,
+                MAX_WINDOW_SIZE >> 20,
+            ),
+        ));
+    }
+
+    if let Some(cache) = cache {
+        cache.insert(&key, &compressed_bytes);
+    }
+
+    Ok(compressed_bytes)
+}
+
+/// Returns the window size the decoder needs for `frame`, the output of compressing `content_size` bytes.
+fn window_size(frame: &[u8], content_size: usize) -> u64 {
+    let descriptor = frame[4];
+    // Single segment frames are decoded with a window as large as their content
+    if descriptor & 0x20 != 0 {
+        return content_size as u64;
+    }
+
+    let window_descriptor = frame[5];
+    let window_base = 1 << (10 + (window_descriptor >> 3));
+    window_base + window_base / 8 * u64::from(window_descriptor & 0x7)
+}
+
+/// Returns the SHA-256 hash of the uncompressed `bytes`, exposed as the content hash of the embedded data.
+fn content_hash(bytes: &[u8]) -> Vec<u8> {
+    Sha256::digest(bytes).to_vec()
+}
+
+fn crate_name(input: &MacroInput) -> syn::Result<String> {
+    let crate_name = proc_macro_crate::crate_name("include-zstd").map_err(|err| {
+        syn::Error::new_spanned(
+            &input.path,
+            format!("failed to locate the `include-zstd` crate: {err}"),
+        )
+    })?;
 
-    let crate_name = proc_macro_crate::crate_name("include-zstd").unwrap();
-    let crate_name = match crate_name {
+    Ok(match crate_name {
         FoundCrate::Itself => "include_zstd".to_string(),
         FoundCrate::Name(name) => name,
-    };
+    })
+}
 
-    format!(
-        r#"unsafe {{ {crate_name}::EmbeddedZstd::<{compressed_bytes_len}>::new_unchecked({}) }}"#,
-        String::from("[")
-            + &compressed_bytes
-                .into_iter()
-                .map(|b| b.to_string())
-                .collect::<Vec<_>>()
-                .join(",")
-            + "]",
-    )
-    .parse()
-    .unwrap()
+/// Returns an item that references `path` through `include_bytes!`, which registers the file in rustc's dep-info so
+/// cargo rebuilds when it changes.
+fn tracked_path(input: &MacroInput, path: &Path) -> syn::Result<String> {
+    let path = path.to_str().ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.path,
+            format!("path `{}` is not valid UTF-8", path.display()),
+        )
+    })?;
+
+    Ok(format!(
+        "const _: &[u8] = ::core::include_bytes!({path:?});"
+    ))
+}
+
+/// Prefix of the identifiers standing in for compressed data until it is spliced into the expansion.
+const BLOB_PREFIX: &str = "__include_zstd_blob_";
+
+/// Compressed data embedded in an expansion. Each blob is written into the expansion's source as a placeholder, and
+/// replaced with a byte string literal once the rest is parsed, so that multi-megabyte data neither goes through
+/// formatting and lexing nor turns into one token per byte.
+#[derive(Default)]
+struct Blobs(Vec<Vec<u8>>);
+
+impl Blobs {
+    /// Stores `bytes`, returning a placeholder for an expression of type `&'static [u8; N]`.
+    fn insert(&mut self, bytes: Vec<u8>) -> String {
+        self.0.push(bytes);
+        format!("{BLOB_PREFIX}{}", self.0.len() - 1)
+    }
+
+    /// Replaces the placeholders in `tokens` with the blobs they stand for.
+    fn splice(&self, tokens: TokenStream) -> TokenStream {
+        tokens
+            .into_iter()
+            .map(|token| match token {
+                TokenTree::Group(group) => {
+                    let mut spliced = Group::new(group.delimiter(), self.splice(group.stream()));
+                    spliced.set_span(group.span());
+                    spliced.into()
+                }
+                TokenTree::Ident(ident) => {
+                    let index = ident
+                        .to_string()
+                        .strip_prefix(BLOB_PREFIX)
+                        .and_then(|index| index.parse::<usize>().ok());
+                    match index {
+                        Some(index) => Literal::byte_string(&self.0[index]).into(),
+                        None => ident.into(),
+                    }
+                }
+                token => token,
+            })
+            .collect()
+    }
+}
+
+fn emit(input: &MacroInput, tokens: String, blobs: &Blobs) -> syn::Result<TokenStream> {
+    let tokens = tokens.parse().map_err(|err| {
+        syn::Error::new_spanned(&input.path, format!("failed to emit tokens: {err}"))
+    })?;
+
+    Ok(blobs.splice(tokens))
 }
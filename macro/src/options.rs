@@ -0,0 +1,632 @@
+use std::{collections::HashMap, env, fs};
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Ident, Lit, LitInt, LitStr, Token,
+};
+use toml_edit::DocumentMut;
+use zstd::zstd_safe::{zstd_sys::ZSTD_cParameter, CParameter, Strategy};
+
+use crate::{dict::Dictionary, manifest_dir, tracked_path};
+
+/// Environment variable overriding the default compression level.
+const LEVEL_ENV: &str = "INCLUDE_ZSTD_LEVEL";
+/// Environment variable overriding every compression level in debug builds.
+const DEBUG_LEVEL_ENV: &str = "INCLUDE_ZSTD_DEBUG_LEVEL";
+
+/// Arguments of the macros: a path, an optional positional compression level, and `key = value` options. The path
+/// is only optional for `include_zstd_dictionary!`, which can train a dictionary instead of reading one.
+pub struct MacroInput {
+    pub path: Option<LitStr>,
+    pub compression_level: Option<LitInt>,
+    pub options: Punctuated<MacroOption, Token![,]>,
+}
+
+impl Parse for MacroInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = if input.peek(Ident) && input.peek2(Token![=]) {
+            None
+        } else {
+            Some(input.parse()?)
+        };
+        let mut compression_level = None;
+        let mut options = Punctuated::new();
+
+        if !input.is_empty() {
+            if path.is_some() {
+                input.parse::<Token![,]>()?;
+            }
+
+            if input.peek(LitInt) {
+                compression_level = Some(input.parse()?);
+                if !input.is_empty() {
+                    input.parse::<Token![,]>()?;
+                }
+            }
+            if !input.is_empty() {
+                options = Punctuated::parse_terminated(input)?;
+            }
+        }
+
+        Ok(Self {
+            path,
+            compression_level,
+            options,
+        })
+    }
+}
+
+/// `key = value` option following the positional arguments.
+pub struct MacroOption {
+    pub key: Ident,
+    _eq: Token![=],
+    value: OptionValue,
+}
+
+impl Parse for MacroOption {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Self {
+            key: input.parse()?,
+            _eq: input.parse()?,
+            value: input.parse()?,
+        })
+    }
+}
+
+impl MacroOption {
+    pub fn error(&self, message: impl std::fmt::Display) -> syn::Error {
+        match &self.value {
+            OptionValue::Lit(lit) => syn::Error::new_spanned(lit, message),
+            OptionValue::List(bracket, _) => syn::Error::new(bracket.span.join(), message),
+        }
+    }
+
+    pub fn bool(&self) -> syn::Result<bool> {
+        match &self.value {
+            OptionValue::Lit(Lit::Bool(lit)) => Ok(lit.value),
+            _ => Err(self.error(format!("expected a boolean for `{}`", self.key))),
+        }
+    }
+
+    pub fn int(&self) -> syn::Result<&LitInt> {
+        match &self.value {
+            OptionValue::Lit(Lit::Int(lit)) => Ok(lit),
+            _ => Err(self.error(format!("expected an integer for `{}`", self.key))),
+        }
+    }
+
+    pub fn str(&self) -> syn::Result<&LitStr> {
+        match &self.value {
+            OptionValue::Lit(Lit::Str(lit)) => Ok(lit),
+            _ => Err(self.error(format!("expected a string for `{}`", self.key))),
+        }
+    }
+
+    pub fn str_list(&self) -> syn::Result<Vec<&LitStr>> {
+        let OptionValue::List(_, items) = &self.value else {
+            return Err(self.error(format!("expected a list of strings for `{}`", self.key)));
+        };
+
+        items
+            .iter()
+            .map(|item| match item {
+                Lit::Str(lit) => Ok(lit),
+                lit => Err(syn::Error::new_spanned(lit, "expected a string")),
+            })
+            .collect()
+    }
+}
+
+enum OptionValue {
+    Lit(Lit),
+    List(syn::token::Bracket, Punctuated<Lit, Token![,]>),
+}
+
+impl Parse for OptionValue {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(syn::token::Bracket) {
+            let content;
+            let bracket = bracketed!(content in input);
+            Ok(Self::List(bracket, Punctuated::parse_terminated(&content)?))
+        } else {
+            Ok(Self::Lit(input.parse()?))
+        }
+    }
+}
+
+/// Options of `input` keyed by name.
+type Options<'a> = HashMap<String, &'a MacroOption>;
+
+/// Returns the options of `input` keyed by name, rejecting keys missing from `known` and keys given more than once.
+fn collect_options<'a>(input: &'a MacroInput, known: &[&str]) -> syn::Result<Options<'a>> {
+    let mut options = HashMap::new();
+
+    for option in &input.options {
+        let key = option.key.to_string();
+        if !known.contains(&key.as_str()) {
+            return Err(syn::Error::new_spanned(
+                &option.key,
+                format!(
+                    "unknown option `{key}`, expected one of: {}",
+                    known
+                        .iter()
+                        .map(|known| format!("`{known}`"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            ));
+        }
+        if options.insert(key, option).is_some() {
+            return Err(syn::Error::new_spanned(
+                &option.key,
+                format!("option `{}` is given more than once", option.key),
+            ));
+        }
+    }
+
+    Ok(options)
+}
+
+/// Options controlling how data is compressed, accepted by every macro.
+pub struct CompressOptions {
+    pub level: i32,
+    /// Advanced encoder parameters, applied on top of the level.
+    pub params: Vec<CParameter>,
+    pub dict: Option<Dictionary>,
+    /// Items that make cargo rebuild when the configuration the options were resolved from changes.
+    pub tracked: String,
+}
+
+impl CompressOptions {
+    const KEYS: &'static [&'static str] = &[
+        "level",
+        "debug_level",
+        "window_log",
+        "hash_log",
+        "chain_log",
+        "target_length",
+        "strategy",
+        "long_distance_matching",
+        "enable_long",
+        "workers",
+        "checksum",
+        "dict",
+        "dict_samples",
+        "dict_size",
+    ];
+
+    /// Parses the options of `include_zstd!` and `include_zstd_str!`.
+    pub fn parse(input: &MacroInput) -> syn::Result<Self> {
+        let options = collect_options(input, Self::KEYS)?;
+
+        Self::from_options(input, &options)
+    }
+
+    fn from_options(input: &MacroInput, options: &Options) -> syn::Result<Self> {
+        let mut tracked = String::new();
+
+        let lit = match (&input.compression_level, options.get("level")) {
+            (Some(_), Some(option)) => {
+                return Err(syn::Error::new_spanned(
+                    &option.key,
+                    "compression level is given both positionally and as `level`",
+                ))
+            }
+            (Some(lit), None) => Some(lit),
+            (None, Some(option)) => Some(option.int()?),
+            (None, None) => None,
+        };
+        let level = lit.map(level_from_lit).transpose()?;
+        // Checked in every profile so that switching to a release build doesn't surface new errors
+        let debug_level = options
+            .get("debug_level")
+            .map(|option| option.int().and_then(level_from_lit))
+            .transpose()?;
+
+        // Proc macros are built with the debug assertions setting of the profile they are used from, which is the
+        // closest signal to the invoking crate's profile that is available to them
+        let debug_level = match debug_level {
+            Some(level) if cfg!(debug_assertions) => Some(level),
+            Some(_) => None,
+            None if cfg!(debug_assertions) => {
+                configured_level(input, DEBUG_LEVEL_ENV, "debug-level", &mut tracked)?
+            }
+            None => None,
+        };
+        let level = match (debug_level, level) {
+            (Some(level), _) | (None, Some(level)) => level,
+            (None, None) => configured_level(input, LEVEL_ENV, "level", &mut tracked)?
+                .unwrap_or(zstd::DEFAULT_COMPRESSION_LEVEL),
+        };
+
+        let dict = dictionary(
+            input,
+            options,
+            "dict",
+            "dict_samples",
+            "dict_size",
+            &mut tracked,
+        )?;
+
+        Ok(Self {
+            level,
+            params: encoder_params(options)?,
+            dict,
+            tracked,
+        })
+    }
+
+    /// Describes every parameter that affects the compressed output, for keying the compression cache. The dictionary
+    /// is left to the cache, which hashes it whole.
+    pub fn encoder_params(&self) -> String {
+        format!(
+            "level={} params={:?} dict={:?}",
+            self.level,
+            self.params,
+            self.dict.as_ref().map(|dict| dict.id)
+        )
+    }
+}
+
+fn level_from_lit(lit: &LitInt) -> syn::Result<i32> {
+    check_level(lit.base10_parse()?).map_err(|err| syn::Error::new_spanned(lit, err))
+}
+
+/// Returns `level` if it is within the range accepted by Zstd, or an error message otherwise.
+fn check_level(level: i32) -> Result<i32, String> {
+    if !zstd::compression_level_range().contains(&level) {
+        return Err(format!(
+            "compression level {level} is out of range, expected a value in {}..={}",
+            zstd::compression_level_range().start(),
+            zstd::compression_level_range().end(),
+        ));
+    }
+
+    Ok(level)
+}
+
+/// Largest window the runtime decoder accepts. Frames needing more fail to decode with
+/// `FrameDecoderError::WindowSizeTooBig`.
+pub const MAX_WINDOW_SIZE: u64 = 100 << 20;
+
+/// Window log used by `enable_long`, the largest whose window fits in `MAX_WINDOW_SIZE`.
+const LONG_WINDOW_LOG: u32 = 26;
+
+/// Collects the advanced encoder parameters from `options`, checking them against the ranges Zstd accepts.
+fn encoder_params(options: &Options) -> syn::Result<Vec<CParameter>> {
+    let mut params = Vec::new();
+
+    let int = |key: &str, param: ZSTD_cParameter| -> syn::Result<Option<u32>> {
+        let Some(option) = options.get(key) else {
+            return Ok(None);
+        };
+        let lit = option.int()?;
+        let value = lit.base10_parse()?;
+
+        // SAFETY: `ZSTD_cParam_getBounds` only reads constants
+        let bounds = unsafe { zstd::zstd_safe::zstd_sys::ZSTD_cParam_getBounds(param) };
+        let (min, max) = (bounds.lowerBound as u32, bounds.upperBound as u32);
+        if !(min..=max).contains(&value) {
+            return Err(syn::Error::new_spanned(
+                lit,
+                format!("`{key}` of {value} is out of range, expected a value in {min}..={max}"),
+            ));
+        }
+
+        Ok(Some(value))
+    };
+    let flag = |key: &str| options.get(key).map(|option| option.bool()).transpose();
+
+    let window_log = int("window_log", ZSTD_cParameter::ZSTD_c_windowLog)?;
+    if let Some(window_log) = window_log.filter(|&log| 1 << log > MAX_WINDOW_SIZE) {
+        return Err(options["window_log"].error(format!(
+            "`window_log` of {window_log} exceeds the {} MiB window the decoder supports, expected at most \
+             {LONG_WINDOW_LOG}",
+            MAX_WINDOW_SIZE >> 20
+        )));
+    }
+    let hash_log = int("hash_log", ZSTD_cParameter::ZSTD_c_hashLog)?;
+    let chain_log = int("chain_log", ZSTD_cParameter::ZSTD_c_chainLog)?;
+    let target_length = int("target_length", ZSTD_cParameter::ZSTD_c_targetLength)?;
+    let workers = int("workers", ZSTD_cParameter::ZSTD_c_nbWorkers)?.filter(|&workers| workers > 0);
+    let strategy = options
+        .get("strategy")
+        .map(|option| strategy(option.str()?))
+        .transpose()?;
+
+    // `enable_long` mirrors `zstd --long`, with the window capped to what the decoder supports
+    let enable_long = flag("enable_long")?.unwrap_or(false);
+    let long_distance_matching = flag("long_distance_matching")?.unwrap_or(enable_long);
+    let window_log = window_log.or(enable_long.then_some(LONG_WINDOW_LOG));
+
+    params.extend(window_log.map(CParameter::WindowLog));
+    params.extend(hash_log.map(CParameter::HashLog));
+    params.extend(chain_log.map(CParameter::ChainLog));
+    params.extend(target_length.map(CParameter::TargetLength));
+    params.extend(strategy.map(CParameter::Strategy));
+    if long_distance_matching {
+        params.push(CParameter::EnableLongDistanceMatching(true));
+    }
+    params.extend(workers.map(CParameter::NbWorkers));
+    if flag("checksum")?.unwrap_or(false) {
+        params.push(CParameter::ChecksumFlag(true));
+    }
+
+    Ok(params)
+}
+
+fn strategy(lit: &LitStr) -> syn::Result<Strategy> {
+    const STRATEGIES: &[(&str, Strategy)] = &[
+        ("fast", Strategy::ZSTD_fast),
+        ("dfast", Strategy::ZSTD_dfast),
+        ("greedy", Strategy::ZSTD_greedy),
+        ("lazy", Strategy::ZSTD_lazy),
+        ("lazy2", Strategy::ZSTD_lazy2),
+        ("btlazy2", Strategy::ZSTD_btlazy2),
+        ("btopt", Strategy::ZSTD_btopt),
+        ("btultra", Strategy::ZSTD_btultra),
+        ("btultra2", Strategy::ZSTD_btultra2),
+    ];
+
+    let name = lit.value();
+    STRATEGIES
+        .iter()
+        .find(|(known, _)| *known == name)
+        .map(|&(_, strategy)| strategy)
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                lit,
+                format!(
+                    "unknown strategy `{name}`, expected one of: {}",
+                    STRATEGIES
+                        .iter()
+                        .map(|(known, _)| format!("`{known}`"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            )
+        })
+}
+
+/// Resolves a project-wide compression level from the `env_var` environment variable, then the `key` entry of
+/// `[package.metadata.include-zstd]` in the invoking crate's `Cargo.toml`.
+fn configured_level(
+    input: &MacroInput,
+    env_var: &str,
+    key: &str,
+    tracked: &mut String,
+) -> syn::Result<Option<i32>> {
+    // `option_env!` registers the variable in rustc's dep-info, so cargo rebuilds when it changes
+    *tracked +=
+        &format!("const _: ::core::option::Option<&str> = ::core::option_env!({env_var:?});");
+
+    if let Ok(level) = env::var(env_var) {
+        let level = level.trim().parse().map_err(|err| {
+            syn::Error::new_spanned(
+                &input.path,
+                format!("invalid compression level `{level}` in `{env_var}`: {err}"),
+            )
+        })?;
+        return check_level(level)
+            .map(Some)
+            .map_err(|err| syn::Error::new_spanned(&input.path, format!("{err} in `{env_var}`")));
+    }
+
+    let manifest_path = manifest_dir(input)?.join("Cargo.toml");
+    // Several lookups may read the manifest, but tracking it once is enough
+    let manifest_tracked = tracked_path(input, &manifest_path)?;
+    if !tracked.contains(&manifest_tracked) {
+        *tracked += &manifest_tracked;
+    }
+
+    let manifest = fs::read_to_string(&manifest_path)
+        .map_err(|err| err.to_string())
+        .and_then(|manifest| {
+            manifest
+                .parse::<DocumentMut>()
+                .map_err(|err| err.to_string())
+        })
+        .map_err(|err| {
+            syn::Error::new_spanned(
+                &input.path,
+                format!("failed to read `{}`: {err}", manifest_path.display()),
+            )
+        })?;
+
+    let Some(level) = manifest
+        .get("package")
+        .and_then(|package| package.get("metadata"))
+        .and_then(|metadata| metadata.get("include-zstd"))
+        .and_then(|config| config.get(key))
+    else {
+        return Ok(None);
+    };
+
+    level
+        .as_integer()
+        .and_then(|level| i32::try_from(level).ok())
+        .ok_or_else(|| String::from("compression level must be an integer"))
+        .and_then(check_level)
+        .map(Some)
+        .map_err(|err| {
+            syn::Error::new_spanned(
+                &input.path,
+                format!(
+                    "{err} in `{key}` of `[package.metadata.include-zstd]` in `{}`",
+                    manifest_path.display()
+                ),
+            )
+        })
+}
+
+/// Options accepted by `include_zstd_dir!`.
+pub struct DirOptions {
+    pub compress: CompressOptions,
+    pub include: Option<GlobSet>,
+    pub exclude: Option<GlobSet>,
+    pub gitignore: bool,
+    pub solid: bool,
+}
+
+impl DirOptions {
+    const KEYS: &'static [&'static str] = &["include", "exclude", "gitignore", "solid"];
+
+    pub fn parse(input: &MacroInput) -> syn::Result<Self> {
+        let options = collect_options(input, &[CompressOptions::KEYS, Self::KEYS].concat())?;
+
+        let glob_set = |key: &str| {
+            options
+                .get(key)
+                .map(|option| build_glob_set(option))
+                .transpose()
+        };
+        let flag = |key: &str| {
+            options
+                .get(key)
+                .map(|option| option.bool())
+                .transpose()
+                .map(|value| value.unwrap_or(false))
+        };
+
+        let compress = CompressOptions::from_options(input, &options)?;
+        let solid = flag("solid")?;
+        if let (true, Some(option)) = (solid, options.get("dict").or(options.get("dict_samples"))) {
+            return Err(syn::Error::new_spanned(
+                &option.key,
+                "dictionaries are not supported in solid mode, the archive is compressed as one frame already",
+            ));
+        }
+
+        Ok(Self {
+            compress,
+            include: glob_set("include")?,
+            exclude: glob_set("exclude")?,
+            gitignore: flag("gitignore")?,
+            solid,
+        })
+    }
+
+    /// Returns whether the file at `relative_path` passes the include and exclude filters.
+    pub fn is_match(&self, relative_path: &str) -> bool {
+        self.include
+            .as_ref()
+            .is_none_or(|include| include.is_match(relative_path))
+            && !self
+                .exclude
+                .as_ref()
+                .is_some_and(|exclude| exclude.is_match(relative_path))
+    }
+}
+
+fn build_glob_set(option: &MacroOption) -> syn::Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in option.str_list()? {
+        let glob = GlobBuilder::new(&pattern.value())
+            .literal_separator(true)
+            .build()
+            .map_err(|err| syn::Error::new_spanned(pattern, format!("invalid glob: {err}")))?;
+        builder.add(glob);
+    }
+
+    builder
+        .build()
+        .map_err(|err| option.error(format!("invalid glob set: {err}")))
+}
+
+/// Loads the dictionary at the path given by `path_key`, or trains one on the samples given by `samples_key`, with
+/// the size given by `size_key`. The files read are tracked, so that cargo rebuilds when they change.
+fn dictionary(
+    input: &MacroInput,
+    options: &Options,
+    path_key: &str,
+    samples_key: &str,
+    size_key: &str,
+    tracked: &mut String,
+) -> syn::Result<Option<Dictionary>> {
+    let size = options.get(size_key);
+    let (dict, paths) = match (options.get(path_key), options.get(samples_key)) {
+        (Some(_), Some(option)) => {
+            return Err(syn::Error::new_spanned(
+                &option.key,
+                format!("`{path_key}` and `{samples_key}` are mutually exclusive"),
+            ))
+        }
+        (Some(option), None) => {
+            if let Some(size) = size {
+                return Err(syn::Error::new_spanned(
+                    &size.key,
+                    format!(
+                        "`{size_key}` only applies to dictionaries trained with `{samples_key}`"
+                    ),
+                ));
+            }
+            let (dict, path) = Dictionary::load(input, option.str()?)?;
+            (dict, vec![path])
+        }
+        (None, Some(option)) => Dictionary::train(
+            input,
+            option.str()?,
+            size.map(|size| size.int()).transpose()?,
+        )?,
+        (None, None) => {
+            if let Some(size) = size {
+                return Err(syn::Error::new_spanned(
+                    &size.key,
+                    format!("`{size_key}` is given without `{samples_key}`"),
+                ));
+            }
+            return Ok(None);
+        }
+    };
+
+    for path in paths {
+        *tracked += &tracked_path(input, &path)?;
+    }
+    Ok(Some(dict))
+}
+
+/// Options accepted by `include_zstd_dictionary!`, along with the dictionary they designate.
+pub struct DictOptions {
+    pub dict: Dictionary,
+    /// Items that make cargo rebuild when the dictionary or its samples change.
+    pub tracked: String,
+}
+
+impl DictOptions {
+    const KEYS: &'static [&'static str] = &["samples", "size"];
+
+    pub fn parse(input: &MacroInput) -> syn::Result<Self> {
+        if let Some(lit) = &input.compression_level {
+            return Err(syn::Error::new_spanned(
+                lit,
+                "dictionaries are embedded as is, without a compression level",
+            ));
+        }
+        let mut options = collect_options(input, Self::KEYS)?;
+
+        // The path is handled like a `path` option, so that it is checked against `samples` and `size` the same way
+        let path_option;
+        if let Some(path) = &input.path {
+            path_option = MacroOption {
+                key: Ident::new("path", path.span()),
+                _eq: Default::default(),
+                value: OptionValue::Lit(Lit::Str(path.clone())),
+            };
+            options.insert(String::from("path"), &path_option);
+        }
+
+        let mut tracked = String::new();
+        let dict = dictionary(input, &options, "path", "samples", "size", &mut tracked)?
+            .ok_or_else(|| {
+                syn::Error::new_spanned(
+                    &input.path,
+                    "expected the path of a dictionary, or `samples` to train one on",
+                )
+            })?;
+
+        Ok(Self { dict, tracked })
+    }
+}
@@ -0,0 +1,147 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{DecompressError, EmbeddedZstdSlice};
+
+/// # `EmbeddedZstdArchive`
+/// Opaque struct that holds a directory tree of files compressed together into a single Zstd frame.
+///
+/// Compressing the files together lets Zstd exploit redundancy across files, which shrinks collections of many small,
+/// similar files considerably. In exchange, the whole archive is decompressed at once, see
+/// [`decompress`](Self::decompress).
+///
+/// See [`include_zstd_dir!`](crate::include_zstd_dir) with `solid = true` for information on how to create an instance
+/// of this struct.
+///
+/// ## Usage
+/// ```rust
+/// use include_zstd::{EmbeddedZstdArchive, include_zstd_dir};
+///
+/// const DATA_ARCHIVE: EmbeddedZstdArchive = include_zstd_dir!("data", 19, solid = true);
+///
+/// // The archive is decompressed once, after which every file is available
+/// let data = DATA_ARCHIVE.decompress().unwrap();
+/// assert_eq!(data.get("udhr_en.txt").unwrap(), include_bytes!("../data/udhr_en.txt"));
+///
+/// // The archive reports how large the files would be if they were compressed separately
+/// println!(
+///     "{} bytes as a single frame, {} bytes as separate frames",
+///     DATA_ARCHIVE.size(),
+///     DATA_ARCHIVE.separate_size(),
+/// );
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EmbeddedZstdArchive {
+    data: EmbeddedZstdSlice<'static>,
+    entries: &'static [(&'static str, Range<usize>)],
+    separate_size: usize,
+}
+
+impl EmbeddedZstdArchive {
+    #[doc(hidden)]
+    #[must_use]
+    pub const unsafe fn new_unchecked(
+        data: EmbeddedZstdSlice<'static>,
+        entries: &'static [(&'static str, Range<usize>)],
+        separate_size: usize,
+    ) -> Self {
+        Self {
+            data,
+            entries,
+            separate_size,
+        }
+    }
+
+    /// Returns the compressed frame holding every file.
+    pub const fn as_slice(&self) -> EmbeddedZstdSlice<'static> {
+        self.data
+    }
+
+    /// Returns the size of the compressed archive, in bytes.
+    pub const fn size(&self) -> usize {
+        self.data.size()
+    }
+
+    /// Returns the total size of the decompressed files, in bytes.
+    pub const fn decompressed_size(&self) -> usize {
+        self.data.decompressed_size()
+    }
+
+    /// Returns the total size the files would take if each was compressed into its own frame, in bytes.
+    ///
+    /// Compare it to [`size`](Self::size) to see how much compressing the files together saves.
+    pub const fn separate_size(&self) -> usize {
+        self.separate_size
+    }
+
+    /// Returns the relative paths of the files in the archive, sorted.
+    pub fn paths(&self) -> impl ExactSizeIterator<Item = &'static str> {
+        self.entries.iter().map(|(path, _)| *path)
+    }
+
+    /// Returns whether a file exists at `path`, relative to the embedded directory.
+    pub fn contains(&self, path: &str) -> bool {
+        self.entries
+            .binary_search_by(|(entry_path, _)| (*entry_path).cmp(path))
+            .is_ok()
+    }
+
+    /// Returns the number of files.
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether there are no files.
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Decompress the whole archive, giving access to every file.
+    pub fn decompress(&self) -> Result<DecompressedArchive, DecompressError> {
+        Ok(DecompressedArchive {
+            data: self.data.decompress()?,
+            entries: self.entries,
+        })
+    }
+}
+
+/// # `DecompressedArchive`
+/// Contents of an [`EmbeddedZstdArchive`], decompressed in a single pass.
+///
+/// Files are borrowed from one shared buffer, so looking them up does not copy or decompress anything.
+pub struct DecompressedArchive {
+    data: Vec<u8>,
+    entries: &'static [(&'static str, Range<usize>)],
+}
+
+impl DecompressedArchive {
+    /// Returns the contents of the file at `path`, relative to the embedded directory.
+    pub fn get(&self, path: &str) -> Option<&[u8]> {
+        self.entries
+            .binary_search_by(|(entry_path, _)| (*entry_path).cmp(path))
+            .ok()
+            .map(|index| &self.data[self.entries[index].1.clone()])
+    }
+
+    /// Returns an iterator over the files and their relative paths, sorted by path.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&'static str, &[u8])> {
+        self.entries
+            .iter()
+            .map(|(path, range)| (*path, &self.data[range.clone()]))
+    }
+
+    /// Returns the number of files.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether there are no files.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the decompressed files concatenated in path order.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+}
@@ -0,0 +1,159 @@
+use alloc::vec::Vec;
+
+use ruzstd::{decoding::dictionary::Dictionary, frame, BlockDecodingStrategy, FrameDecoder};
+
+use crate::{slice::verify_checksum, DecompressError};
+
+/// # `EmbeddedZstdDict`
+/// Opaque struct that holds a Zstd dictionary, identified by its dictionary ID.
+///
+/// Data compressed with `dict = "path"` can only be decompressed with the same dictionary, passed to methods such as
+/// [`EmbeddedZstd::decompress_with_dict`](crate::EmbeddedZstd::decompress_with_dict). Embedding the dictionary once
+/// lets every file compressed with it share it.
+///
+/// The dictionary also works as a runtime codec: [`decompress`](Self::decompress) accepts any frame compressed with
+/// it, such as messages received over the network, and `compress`, behind the `compress` feature, produces them.
+///
+/// See [`include_zstd_dictionary!`](crate::include_zstd_dictionary) for information on how to create an instance of
+/// this struct.
+///
+/// ## Usage
+/// ```rust
+/// use include_zstd::{EmbeddedZstdDict, include_zstd, include_zstd_dictionary};
+///
+/// const MESSAGES_DICT: EmbeddedZstdDict = include_zstd_dictionary!("fixtures/messages.dict");
+///
+/// let message = include_zstd!("fixtures/messages/02.json", 19, dict = "fixtures/messages.dict");
+/// assert_eq!(
+///     message.decompress_with_dict(&MESSAGES_DICT).unwrap(),
+///     include_bytes!("../fixtures/messages/02.json"),
+/// );
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EmbeddedZstdDict {
+    data: &'static [u8],
+    id: u32,
+}
+
+impl EmbeddedZstdDict {
+    #[doc(hidden)]
+    #[must_use]
+    pub const unsafe fn new_unchecked(data: &'static [u8], id: u32) -> Self {
+        Self { data, id }
+    }
+
+    /// Returns the dictionary ID, which frames compressed with the dictionary refer to it by.
+    pub const fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Returns the size of the dictionary, in bytes.
+    pub const fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the dictionary in the format produced by `zstd --train`.
+    pub const fn as_bytes(&self) -> &'static [u8] {
+        self.data
+    }
+
+    /// Decompress a single Zstd frame compressed with the dictionary and return it as a `Vec<u8>` of at most
+    /// `max_size` bytes, reporting corrupted data as a [`DecompressError`].
+    ///
+    /// Unlike embedded data, `frame` is not known at compile time, so a few bytes may expand to any size. Decoding stops
+    /// with [`DecompressError::SizeLimitExceeded`] once the output exceeds `max_size`, and frames declaring a larger
+    /// content size or window are rejected upfront, as the decoder buffers up to a window of output. Frames produced
+    /// by [`compress`](Self::compress) or other one-shot encoders use a window no larger than their content. Bytes
+    /// following the frame are reported as [`DecompressError::TrailingBytes`].
+    ///
+    /// ```rust
+    /// use include_zstd::{EmbeddedZstdDict, include_zstd_dictionary};
+    ///
+    /// const MESSAGES_DICT: EmbeddedZstdDict = include_zstd_dictionary!("fixtures/messages.dict");
+    ///
+    /// // A message compressed with the same dictionary by another peer
+    /// let message = br#"{"id":1,"type":"ping"}"#;
+    /// let frame = zstd::bulk::Compressor::with_dictionary(3, MESSAGES_DICT.as_bytes())
+    ///     .unwrap()
+    ///     .compress(message)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(MESSAGES_DICT.decompress(&frame, 1024).unwrap(), message);
+    /// ```
+    pub fn decompress(&self, frame: &[u8], max_size: usize) -> Result<Vec<u8>, DecompressError> {
+        let size_limit_exceeded = DecompressError::SizeLimitExceeded { limit: max_size };
+
+        let (header, _) =
+            frame::read_frame_header(frame).map_err(DecompressError::ReadFrameHeader)?;
+        let window_size = header
+            .header
+            .window_size()
+            .map_err(DecompressError::FrameHeader)?;
+        if window_size.max(header.header.frame_content_size()) > max_size as u64 {
+            return Err(size_limit_exceeded);
+        }
+
+        let mut decoder = self.decoder(frame)?;
+        let mut source = frame;
+        let mut decompressed = Vec::new();
+
+        decoder.init(&mut source)?;
+        // Decoding a block at a time bounds the output to `max_size` plus one block before the limit is noticed
+        while !decoder.is_finished() {
+            decoder.decode_blocks(&mut source, BlockDecodingStrategy::UptoBlocks(1))?;
+            if let Some(bytes) = decoder.collect() {
+                decompressed.extend_from_slice(&bytes);
+            }
+            if decompressed.len() > max_size {
+                return Err(size_limit_exceeded);
+            }
+        }
+        if !source.is_empty() {
+            return Err(DecompressError::TrailingBytes { len: source.len() });
+        }
+
+        // The decoder hashes its output as it is collected, so the checksum can only be checked afterwards
+        verify_checksum(&decoder)?;
+        Ok(decompressed)
+    }
+
+    /// Compress `data` with the dictionary at the given compression level, returning a frame that
+    /// [`decompress`](Self::decompress) accepts. A level of `0` selects Zstd's default.
+    ///
+    /// Compression uses the reference Zstd implementation, so this is only available with the `compress` feature,
+    /// which requires `std`.
+    ///
+    /// ```rust
+    /// use include_zstd::{EmbeddedZstdDict, include_zstd_dictionary};
+    ///
+    /// const MESSAGES_DICT: EmbeddedZstdDict = include_zstd_dictionary!("fixtures/messages.dict");
+    ///
+    /// let message = br#"{"id":1,"type":"ping"}"#;
+    /// let frame = MESSAGES_DICT.compress(message, 19).unwrap();
+    /// assert_eq!(MESSAGES_DICT.decompress(&frame, 1024).unwrap(), message);
+    /// ```
+    #[cfg(feature = "compress")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "compress")))]
+    pub fn compress(&self, data: &[u8], level: i32) -> std::io::Result<Vec<u8>> {
+        zstd::bulk::Compressor::with_dictionary(level, self.data)?.compress(data)
+    }
+
+    /// Returns a decoder for `frame` loaded with the dictionary, after checking that the frame refers to it.
+    pub(crate) fn decoder(&self, frame: &[u8]) -> Result<FrameDecoder, DecompressError> {
+        let (header, _) =
+            frame::read_frame_header(frame).map_err(DecompressError::ReadFrameHeader)?;
+        if let Some(required) = header.header.dictionary_id() {
+            if required != self.id {
+                return Err(DecompressError::DictionaryMismatch {
+                    required,
+                    provided: self.id,
+                });
+            }
+        }
+
+        let mut decoder = FrameDecoder::new();
+        decoder
+            .add_dict(Dictionary::decode_dict(self.data).map_err(DecompressError::Dictionary)?)?;
+        Ok(decoder)
+    }
+}
@@ -0,0 +1,69 @@
+use crate::EmbeddedZstdSlice;
+
+/// # `EmbeddedZstdDir`
+/// Opaque struct that holds a directory tree of Zstd-compressed files, keyed by their path relative to the directory.
+///
+/// Paths use `/` as the separator on every platform, and entries are sorted by path.
+///
+/// See [`include_zstd_dir!`](crate::include_zstd_dir) for information on how to create an instance of this struct.
+///
+/// ## Usage
+/// ```rust
+/// use include_zstd::{EmbeddedZstdDir, include_zstd_dir};
+///
+/// const DATA_DIR: EmbeddedZstdDir = include_zstd_dir!("data", 19);
+///
+/// // Files can be looked up by their relative path...
+/// let udhr = DATA_DIR.get("udhr_en.txt").unwrap();
+/// assert_eq!(udhr.decompress().unwrap(), include_bytes!("../data/udhr_en.txt"));
+/// assert!(DATA_DIR.get("missing.txt").is_none());
+///
+/// // ...or iterated over
+/// for (path, file) in DATA_DIR.iter() {
+///     println!("{path}: {} -> {} bytes", file.decompressed_size(), file.size());
+/// }
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EmbeddedZstdDir {
+    entries: &'static [(&'static str, EmbeddedZstdSlice<'static>)],
+}
+
+impl EmbeddedZstdDir {
+    #[doc(hidden)]
+    #[must_use]
+    pub const unsafe fn new_unchecked(
+        entries: &'static [(&'static str, EmbeddedZstdSlice<'static>)],
+    ) -> Self {
+        Self { entries }
+    }
+
+    /// Returns the file at `path`, relative to the embedded directory.
+    pub fn get(&self, path: &str) -> Option<EmbeddedZstdSlice<'static>> {
+        self.entries
+            .binary_search_by(|(entry_path, _)| (*entry_path).cmp(path))
+            .ok()
+            .map(|index| self.entries[index].1)
+    }
+
+    /// Returns whether a file exists at `path`, relative to the embedded directory.
+    pub fn contains(&self, path: &str) -> bool {
+        self.get(path).is_some()
+    }
+
+    /// Returns an iterator over the files and their relative paths, sorted by path.
+    pub fn iter(
+        &self,
+    ) -> impl ExactSizeIterator<Item = (&'static str, EmbeddedZstdSlice<'static>)> {
+        self.entries.iter().copied()
+    }
+
+    /// Returns the number of files.
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether there are no files.
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
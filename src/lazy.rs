@@ -0,0 +1,152 @@
+use alloc::boxed::Box;
+use core::{fmt, ops::Deref};
+use std::sync::OnceLock;
+
+use crate::{DecompressError, EmbeddedZstd, EmbeddedZstdStr};
+
+/// # `LazyZstd`
+/// Wrapper around an [`EmbeddedZstd`] that decompresses the data once, on first access, and caches the result.
+///
+/// Access is thread-safe, and a `static` instance hands out `&'static [u8]` just like [`include_bytes!`].
+///
+/// Only available with the `std` feature.
+///
+/// ## Usage
+/// ```rust
+/// use include_zstd::{LazyZstd, include_zstd};
+///
+/// static DATA: LazyZstd<4539> = LazyZstd::new(include_zstd!("data/udhr_en.txt", 19));
+///
+/// // The data is decompressed on first access...
+/// let data: &'static [u8] = DATA.get();
+/// assert_eq!(data, include_bytes!("../data/udhr_en.txt"));
+///
+/// // ...and reused afterwards
+/// assert!(std::ptr::eq(data, DATA.get()));
+/// ```
+pub struct LazyZstd<const SIZE: usize> {
+    compressed: EmbeddedZstd<SIZE>,
+    decompressed: OnceLock<Box<[u8]>>,
+}
+
+impl<const SIZE: usize> LazyZstd<SIZE> {
+    /// Wraps `compressed`, deferring decompression until the data is first accessed.
+    #[must_use]
+    pub const fn new(compressed: EmbeddedZstd<SIZE>) -> Self {
+        Self {
+            compressed,
+            decompressed: OnceLock::new(),
+        }
+    }
+
+    /// Returns the compressed data.
+    pub const fn compressed(&self) -> &EmbeddedZstd<SIZE> {
+        &self.compressed
+    }
+
+    /// Returns the decompressed data, decompressing it first if this is the first access.
+    ///
+    /// ## Panics
+    /// Panics if the data is corrupted, see [`LazyZstd::try_get`] for a fallible alternative.
+    pub fn get(&self) -> &[u8] {
+        self.try_get().unwrap()
+    }
+
+    /// Returns the decompressed data, decompressing it first if this is the first access.
+    ///
+    /// Failed decompressions are not cached, so the next access tries again.
+    pub fn try_get(&self) -> Result<&[u8], DecompressError> {
+        if let Some(decompressed) = self.decompressed.get() {
+            return Ok(decompressed);
+        }
+
+        let decompressed = self.compressed.decompress()?.into_boxed_slice();
+        Ok(self.decompressed.get_or_init(|| decompressed))
+    }
+}
+
+impl<const SIZE: usize> Deref for LazyZstd<SIZE> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
+
+impl<const SIZE: usize> AsRef<[u8]> for LazyZstd<SIZE> {
+    fn as_ref(&self) -> &[u8] {
+        self.get()
+    }
+}
+
+impl<const SIZE: usize> fmt::Debug for LazyZstd<SIZE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyZstd")
+            .field("size", &SIZE)
+            .field("decompressed_size", &self.compressed.decompressed_size())
+            .field("initialized", &self.decompressed.get().is_some())
+            .finish()
+    }
+}
+
+/// # `LazyZstdStr`
+/// Wrapper around an [`EmbeddedZstdStr`] that decompresses the text once, on first access, and caches the result.
+///
+/// See [`LazyZstd`] for details. Only available with the `std` feature.
+///
+/// ## Usage
+/// ```rust
+/// use include_zstd::{LazyZstdStr, include_zstd_str};
+///
+/// static TEXT: LazyZstdStr<4539> = LazyZstdStr::new(include_zstd_str!("data/udhr_en.txt", 19));
+///
+/// let text: &'static str = TEXT.get();
+/// assert!(text.starts_with("Universal Declaration of Human Rights"));
+/// ```
+pub struct LazyZstdStr<const SIZE: usize>(LazyZstd<SIZE>);
+
+impl<const SIZE: usize> LazyZstdStr<SIZE> {
+    /// Wraps `compressed`, deferring decompression until the text is first accessed.
+    #[must_use]
+    pub const fn new(compressed: EmbeddedZstdStr<SIZE>) -> Self {
+        Self(LazyZstd::new(*compressed.compressed()))
+    }
+
+    /// Returns the decompressed text, decompressing it first if this is the first access.
+    ///
+    /// ## Panics
+    /// Panics if the data is corrupted, see [`LazyZstdStr::try_get`] for a fallible alternative.
+    pub fn get(&self) -> &str {
+        self.try_get().unwrap()
+    }
+
+    /// Returns the decompressed text, decompressing it first if this is the first access.
+    ///
+    /// Failed decompressions are not cached, so the next access tries again.
+    pub fn try_get(&self) -> Result<&str, DecompressError> {
+        let bytes = self.0.try_get()?;
+
+        // SAFETY: the text was validated as UTF-8 when it was embedded
+        Ok(unsafe { core::str::from_utf8_unchecked(bytes) })
+    }
+}
+
+impl<const SIZE: usize> Deref for LazyZstdStr<SIZE> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
+
+impl<const SIZE: usize> AsRef<str> for LazyZstdStr<SIZE> {
+    fn as_ref(&self) -> &str {
+        self.get()
+    }
+}
+
+impl<const SIZE: usize> fmt::Debug for LazyZstdStr<SIZE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LazyZstdStr").field(&self.0).finish()
+    }
+}
@@ -7,25 +7,77 @@
 //! use include_zstd::{EmbeddedZstd, include_zstd};
 //!
 //! // Create an instance of `EmbeddedZstd` using the `include_zstd!` macro
-//! const COMPRESSED_DATA: EmbeddedZstd<4538> = include_zstd!("data/udhr_en.txt", 19);
+//! const COMPRESSED_DATA: EmbeddedZstd<4539> = include_zstd!("data/udhr_en.txt", 19);
 //!
 //! // The compressed data can be converted into a `Vec<u8>` or a `Box<[u8]>`
 //! let compressed_data_vec: Vec<u8> = COMPRESSED_DATA.into();
 //! let compressed_data_box: Box<[u8]> = COMPRESSED_DATA.into();
 //!
 //! // The compressed data can also be compared to other instances of `EmbeddedZstd`
-//! const OTHER_COMPRESSED_DATA: EmbeddedZstd<4538> = include_zstd!("data/udhr_en.txt", 19);
+//! const OTHER_COMPRESSED_DATA: EmbeddedZstd<4539> = include_zstd!("data/udhr_en.txt", 19);
 //! assert!(COMPRESSED_DATA == OTHER_COMPRESSED_DATA);
 //! ```
 //!
+//! ## Features
+//! - `std` *(default)*: implements [`std::error::Error`] for the error types and enables [`EmbeddedZstd::reader`],
+//!   [`LazyZstd`] and [`LazyZstdStr`]. Without it, the crate is `#![no_std]` and only depends on `alloc`.
+//! - `verify`: enables [`EmbeddedZstd::verify`] and the other `verify` methods, which check decompressed data against
+//!   the SHA-256 hash computed at compile time.
+//! - `compress`: enables [`EmbeddedZstdDict::compress`], which compresses data with an embedded dictionary at runtime.
+//!   It depends on the reference Zstd implementation, written in C, and requires `std`.
+//!
+//! Every feature set requires a global allocator, as the decoder keeps its state on the heap, along with a buffer of up
+//! to a window of output. [`EmbeddedZstd::decompress_into`] and [`EmbeddedZstd::decompress_array`] write to memory
+//! owned by the caller, but still decode through that buffer, so their heap usage is O(window). Unless `window_log` is
+//! set, the window of a file is as large as the file itself, see [Options](macro@include_zstd#options). Targets
+//! without an allocator cannot use the crate.
+//!
+//! ## Compression cache
+//! Compressed output is cached under the target directory of the current profile, in `include-zstd-cache`, keyed by
+//! a hash of the input, the compression parameters and the Zstd version. Expanding a macro again only compresses files
+//! that changed. The `INCLUDE_ZSTD_CACHE_DIR` environment variable moves the cache elsewhere, or disables it when
+//! empty. Deleting the directory is always safe. Dictionaries trained by [`include_zstd_dictionary!`] are cached
+//! the same way, keyed by a hash of their samples and size.
+//!
 
-use std::io::Read;
+#![no_std]
+#![cfg_attr(docsrs, feature(doc_cfg))]
 
-use ruzstd::StreamingDecoder;
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{fmt, str::Utf8Error};
+
+use ruzstd::{
+    decoding::{
+        block_decoder::{BlockHeaderReadError, DecodeBlockContentError},
+        dictionary::DictionaryDecodeError,
+    },
+    frame::{FrameHeaderError, ReadFrameHeaderError},
+    frame_decoder::FrameDecoderError,
+};
 
 #[doc(hidden)]
 pub extern crate include_zstd_macro;
 
+mod archive;
+mod dict;
+mod dir;
+#[cfg(feature = "std")]
+mod lazy;
+mod slice;
+mod text;
+
+pub use archive::{DecompressedArchive, EmbeddedZstdArchive};
+pub use dict::EmbeddedZstdDict;
+pub use dir::EmbeddedZstdDir;
+#[cfg(feature = "std")]
+pub use lazy::{LazyZstd, LazyZstdStr};
+pub use slice::EmbeddedZstdSlice;
+pub use text::EmbeddedZstdStr;
+
 /// # `EmbeddedZstd`
 /// Opaque struct that holds Zstd-compressed data.
 ///
@@ -36,40 +88,283 @@ pub extern crate include_zstd_macro;
 /// use include_zstd::{EmbeddedZstd, include_zstd};
 ///
 /// // Create an instance of `EmbeddedZstd` using the `include_zstd!` macro
-/// const COMPRESSED_DATA: EmbeddedZstd<4538> = include_zstd!("data/udhr_en.txt", 19);
+/// const COMPRESSED_DATA: EmbeddedZstd<4539> = include_zstd!("data/udhr_en.txt", 19);
 ///
 /// // The compressed data can be converted into a `Vec<u8>` or a `Box<[u8]>`
 /// let compressed_data_vec: Vec<u8> = COMPRESSED_DATA.into();
 /// let compressed_data_box: Box<[u8]> = COMPRESSED_DATA.into();
 ///
 /// // The compressed data can also be compared to other instances of `EmbeddedZstd`
-/// const OTHER_COMPRESSED_DATA: EmbeddedZstd<4538> = include_zstd!("data/udhr_en.txt", 19);
+/// const OTHER_COMPRESSED_DATA: EmbeddedZstd<4539> = include_zstd!("data/udhr_en.txt", 19);
 /// assert!(COMPRESSED_DATA == OTHER_COMPRESSED_DATA);
-#[repr(transparent)]
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct EmbeddedZstd<const SIZE: usize>([u8; SIZE]);
+pub struct EmbeddedZstd<const SIZE: usize> {
+    data: [u8; SIZE],
+    decompressed_size: usize,
+    content_hash: [u8; 32],
+}
 
 impl<const SIZE: usize> EmbeddedZstd<SIZE> {
     #[doc(hidden)]
     #[must_use]
-    pub const unsafe fn new_unchecked(data: [u8; SIZE]) -> Self {
-        Self(data)
+    pub const unsafe fn new_unchecked(
+        data: [u8; SIZE],
+        decompressed_size: usize,
+        content_hash: [u8; 32],
+    ) -> Self {
+        Self {
+            data,
+            decompressed_size,
+            content_hash,
+        }
     }
 
     /// Returns the size of the compressed data, in bytes.
     pub const fn size(&self) -> usize {
         SIZE
     }
+
+    /// Returns the size of the decompressed data, in bytes.
+    ///
+    /// ## Usage
+    /// ```rust
+    /// use include_zstd::{EmbeddedZstd, include_zstd};
+    ///
+    /// const COMPRESSED_DATA: EmbeddedZstd<4539> = include_zstd!("data/udhr_en.txt", 19);
+    ///
+    /// // The size is known at compile time, so it can be checked in `const` assertions
+    /// const _: () = assert!(COMPRESSED_DATA.decompressed_size() == 12703);
+    ///
+    /// let mut buf = Vec::with_capacity(COMPRESSED_DATA.decompressed_size());
+    /// buf.extend(COMPRESSED_DATA.decompress().unwrap());
+    /// assert_eq!(buf.len(), buf.capacity());
+    /// ```
+    pub const fn decompressed_size(&self) -> usize {
+        self.decompressed_size
+    }
+
+    /// Returns the SHA-256 hash of the decompressed data, computed at compile time.
+    ///
+    /// The hash identifies the content regardless of how it was compressed, which makes it suitable for cache busting
+    /// or `ETag` headers.
+    ///
+    /// ## Usage
+    /// ```rust
+    /// use include_zstd::{EmbeddedZstd, include_zstd};
+    ///
+    /// const COMPRESSED_DATA: EmbeddedZstd<4539> = include_zstd!("data/udhr_en.txt", 19);
+    /// const CONTENT_HASH: [u8; 32] = COMPRESSED_DATA.content_hash();
+    ///
+    /// // The hash does not depend on the compression level
+    /// assert_eq!(CONTENT_HASH, include_zstd!("data/udhr_en.txt", 1).content_hash());
+    ///
+    /// let etag: String = CONTENT_HASH[..8].iter().map(|byte| format!("{byte:02x}")).collect();
+    /// assert_eq!(etag.len(), 16);
+    /// ```
+    pub const fn content_hash(&self) -> [u8; 32] {
+        self.content_hash
+    }
+
+    /// Returns a borrowed view of the compressed data that does not carry its size in the type.
+    pub const fn as_slice(&self) -> EmbeddedZstdSlice<'_> {
+        // SAFETY: the data, its decompressed size and its hash are carried over unchanged
+        unsafe {
+            EmbeddedZstdSlice::new_unchecked(&self.data, self.decompressed_size, &self.content_hash)
+        }
+    }
+
+    /// Decompress the data and return it as a `Vec<u8>`, reporting corrupted data as a [`DecompressError`].
+    ///
+    /// ## Usage
+    /// ```rust
+    /// use include_zstd::{EmbeddedZstd, include_zstd};
+    ///
+    /// const COMPRESSED_DATA: EmbeddedZstd<4539> = include_zstd!("data/udhr_en.txt", 19);
+    ///
+    /// let data = COMPRESSED_DATA.decompress().unwrap();
+    /// assert_eq!(data, include_bytes!("../data/udhr_en.txt"));
+    ///
+    /// // Data that is not a valid Zstd frame is reported as an error
+    /// let corrupted = unsafe { EmbeddedZstd::new_unchecked([0u8; 16], 0, [0u8; 32]) };
+    /// assert!(corrupted.decompress().is_err());
+    /// ```
+    pub fn decompress(&self) -> Result<Vec<u8>, DecompressError> {
+        self.as_slice().decompress()
+    }
+
+    /// Decompress data compressed with `dict = "path"`, using the dictionary embedded from the same file.
+    ///
+    /// Returns [`DecompressError::DictionaryMismatch`] if the data was compressed with another dictionary. Data
+    /// compressed without a dictionary is decompressed as usual.
+    ///
+    /// ## Usage
+    /// ```rust
+    /// use include_zstd::{DecompressError, EmbeddedZstdDict, include_zstd, include_zstd_dictionary};
+    ///
+    /// const MESSAGES_DICT: EmbeddedZstdDict = include_zstd_dictionary!("fixtures/messages.dict");
+    ///
+    /// let message = include_zstd!("fixtures/messages/02.json", 19, dict = "fixtures/messages.dict");
+    /// assert_eq!(
+    ///     message.decompress_with_dict(&MESSAGES_DICT).unwrap(),
+    ///     include_bytes!("../fixtures/messages/02.json"),
+    /// );
+    ///
+    /// // The dictionary is required
+    /// assert!(matches!(
+    ///     message.decompress(),
+    ///     Err(DecompressError::DictionaryRequired { id }) if id == MESSAGES_DICT.id(),
+    /// ));
+    /// ```
+    pub fn decompress_with_dict(
+        &self,
+        dict: &EmbeddedZstdDict,
+    ) -> Result<Vec<u8>, DecompressError> {
+        self.as_slice().decompress_with_dict(dict)
+    }
+
+    /// Decompress the data into `out`, returning the number of bytes written.
+    ///
+    /// `out` must be at least [`decompressed_size`](Self::decompressed_size) bytes long, otherwise
+    /// [`DecompressError::BufferTooSmall`] is returned.
+    ///
+    /// The decoder still buffers up to a window of output on the heap before copying it to `out`, like
+    /// [`reader`](Self::reader) does. Unless the data was compressed with a lower `window_log`, that buffer is as large
+    /// as `out`, see [Features](crate#features).
+    ///
+    /// ## Usage
+    /// ```rust
+    /// use include_zstd::{EmbeddedZstd, include_zstd};
+    ///
+    /// const COMPRESSED_DATA: EmbeddedZstd<4539> = include_zstd!("data/udhr_en.txt", 19);
+    ///
+    /// let mut buf = [0u8; 16384];
+    /// let len = COMPRESSED_DATA.decompress_into(&mut buf).unwrap();
+    /// assert_eq!(&buf[..len], include_bytes!("../data/udhr_en.txt"));
+    ///
+    /// // Buffers that cannot hold the decompressed data are rejected
+    /// let mut buf = [0u8; 64];
+    /// assert!(COMPRESSED_DATA.decompress_into(&mut buf).is_err());
+    /// ```
+    pub fn decompress_into(&self, out: &mut [u8]) -> Result<usize, DecompressError> {
+        self.as_slice().decompress_into(out)
+    }
+
+    /// Decompress data compressed with a dictionary into `out`, like [`decompress_into`](Self::decompress_into).
+    ///
+    /// ## Usage
+    /// ```rust
+    /// use include_zstd::{EmbeddedZstdDict, include_zstd, include_zstd_dictionary};
+    ///
+    /// const MESSAGES_DICT: EmbeddedZstdDict = include_zstd_dictionary!("fixtures/messages.dict");
+    ///
+    /// let message = include_zstd!("fixtures/messages/02.json", 19, dict = "fixtures/messages.dict");
+    /// let mut buf = [0u8; 1024];
+    /// let len = message.decompress_into_with_dict(&MESSAGES_DICT, &mut buf).unwrap();
+    /// assert_eq!(&buf[..len], include_bytes!("../fixtures/messages/02.json"));
+    /// ```
+    pub fn decompress_into_with_dict(
+        &self,
+        dict: &EmbeddedZstdDict,
+        out: &mut [u8],
+    ) -> Result<usize, DecompressError> {
+        self.as_slice().decompress_into_with_dict(dict, out)
+    }
+
+    /// Decompress the data into a fixed-size array.
+    ///
+    /// `N` must be at least [`decompressed_size`](Self::decompressed_size), otherwise
+    /// [`DecompressError::BufferTooSmall`] is returned. Bytes past the decompressed data are left zeroed.
+    ///
+    /// Like [`decompress_into`](Self::decompress_into), the decoder still buffers up to a window of output on the heap,
+    /// so a global allocator is required even without the `std` feature, see [Features](crate#features).
+    ///
+    /// ## Usage
+    /// ```rust
+    /// use include_zstd::{EmbeddedZstd, include_zstd};
+    ///
+    /// const COMPRESSED_DATA: EmbeddedZstd<4539> = include_zstd!("data/udhr_en.txt", 19);
+    /// const DECOMPRESSED_SIZE: usize = COMPRESSED_DATA.decompressed_size();
+    ///
+    /// let data = COMPRESSED_DATA.decompress_array::<DECOMPRESSED_SIZE>().unwrap();
+    /// assert_eq!(&data, include_bytes!("../data/udhr_en.txt"));
+    /// ```
+    pub fn decompress_array<const N: usize>(&self) -> Result<[u8; N], DecompressError> {
+        self.as_slice().decompress_array()
+    }
+
+    /// Decompress data compressed with a dictionary into a fixed-size array, like
+    /// [`decompress_array`](Self::decompress_array).
+    pub fn decompress_array_with_dict<const N: usize>(
+        &self,
+        dict: &EmbeddedZstdDict,
+    ) -> Result<[u8; N], DecompressError> {
+        self.as_slice().decompress_array_with_dict(dict)
+    }
+
+    /// Decompress the data and check it against its [`content_hash`](Self::content_hash), returning
+    /// [`DecompressError::ContentHashMismatch`] if the embedded bytes were altered.
+    ///
+    /// Only available with the `verify` feature.
+    ///
+    /// ## Usage
+    /// ```rust
+    /// use include_zstd::{EmbeddedZstd, include_zstd};
+    ///
+    /// const COMPRESSED_DATA: EmbeddedZstd<4539> = include_zstd!("data/udhr_en.txt", 19);
+    ///
+    /// # #[cfg(feature = "verify")]
+    /// assert!(COMPRESSED_DATA.verify().is_ok());
+    /// ```
+    #[cfg(feature = "verify")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "verify")))]
+    pub fn verify(&self) -> Result<(), DecompressError> {
+        self.as_slice().verify()
+    }
+
+    /// Decompress data compressed with a dictionary and check it against its [`content_hash`](Self::content_hash),
+    /// like [`verify`](Self::verify).
+    ///
+    /// Only available with the `verify` feature.
+    #[cfg(feature = "verify")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "verify")))]
+    pub fn verify_with_dict(&self, dict: &EmbeddedZstdDict) -> Result<(), DecompressError> {
+        self.as_slice().verify_with_dict(dict)
+    }
+
+    /// Returns a reader that decompresses the data incrementally.
+    ///
+    /// The decoder buffers up to a window of output, the smaller of the decompressed size and the window the data was
+    /// compressed with, and its heap usage is a small multiple of that. As [`include_zstd!`] records the decompressed
+    /// size, the window defaults to the whole content for files up to a few MiB, which are then buffered entirely.
+    /// Set a lower `window_log` for large files meant to be streamed, see [Options](macro@include_zstd#options).
+    ///
+    /// Only available with the `std` feature.
+    ///
+    /// ## Usage
+    /// ```rust
+    /// use std::io::BufRead;
+    ///
+    /// use include_zstd::{EmbeddedZstd, include_zstd};
+    ///
+    /// static COMPRESSED_DATA: EmbeddedZstd<4539> = include_zstd!("data/udhr_en.txt", 19);
+    ///
+    /// let reader = COMPRESSED_DATA.reader().unwrap();
+    /// let first_line = reader.lines().next().unwrap().unwrap();
+    /// assert_eq!(first_line, "Universal Declaration of Human Rights");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn reader(&self) -> Result<impl std::io::BufRead + '_, DecompressError> {
+        self.as_slice().reader()
+    }
 }
 
 impl<const SIZE: usize> From<EmbeddedZstd<SIZE>> for Vec<u8> {
     /// Decompress the data and return it as a `Vec<u8>`
+    ///
+    /// ## Panics
+    /// Panics if the data is corrupted, see [`EmbeddedZstd::decompress`] for a fallible alternative.
     fn from(value: EmbeddedZstd<SIZE>) -> Self {
-        let mut dec = StreamingDecoder::new(value.0.as_ref()).unwrap();
-        let mut buf = Vec::new();
-
-        dec.read_to_end(&mut buf).unwrap();
-        buf
+        value.decompress().unwrap()
     }
 }
 
@@ -80,16 +375,169 @@ impl<const SIZE: usize> From<EmbeddedZstd<SIZE>> for Box<[u8]> {
     }
 }
 
+/// # `DecompressError`
+/// Error returned when the data held by an [`EmbeddedZstd`] cannot be decompressed.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DecompressError {
+    /// The frame header could not be read.
+    ReadFrameHeader(ReadFrameHeaderError),
+    /// The frame header was read but is invalid.
+    FrameHeader(FrameHeaderError),
+    /// A block header could not be read.
+    BlockHeader(BlockHeaderReadError),
+    /// A block body could not be decoded.
+    BlockContent(DecodeBlockContentError),
+    /// The output buffer cannot hold the decompressed data.
+    BufferTooSmall {
+        /// Number of bytes needed to hold the decompressed data.
+        required: usize,
+        /// Number of bytes available in the output buffer.
+        provided: usize,
+    },
+    /// The decompressed data does not match the content checksum stored in the frame, meaning the embedded bytes were
+    /// altered. Only frames compressed with `checksum = true` are verified.
+    ChecksumMismatch {
+        /// Checksum stored in the frame.
+        expected: u32,
+        /// Checksum of the decompressed data.
+        calculated: u32,
+    },
+    /// The decompressed data does not match the SHA-256 hash computed when it was embedded. Only reported by
+    /// [`EmbeddedZstd::verify`] and the other `verify` methods.
+    ContentHashMismatch {
+        /// Hash computed when the data was embedded.
+        expected: [u8; 32],
+        /// Hash of the decompressed data.
+        calculated: [u8; 32],
+    },
+    /// The data was compressed with a dictionary, which must be passed to a method such as
+    /// [`EmbeddedZstd::decompress_with_dict`].
+    DictionaryRequired {
+        /// ID of the dictionary the data was compressed with.
+        id: u32,
+    },
+    /// The data was compressed with another dictionary than the one provided.
+    DictionaryMismatch {
+        /// ID of the dictionary the data was compressed with.
+        required: u32,
+        /// ID of the dictionary provided.
+        provided: u32,
+    },
+    /// The dictionary could not be decoded.
+    Dictionary(DictionaryDecodeError),
+    /// The decompressed data would exceed the size limit given to [`EmbeddedZstdDict::decompress`].
+    SizeLimitExceeded {
+        /// Largest number of decompressed bytes allowed.
+        limit: usize,
+    },
+    /// Bytes follow the frame passed to [`EmbeddedZstdDict::decompress`].
+    TrailingBytes {
+        /// Number of bytes following the frame.
+        len: usize,
+    },
+    /// Text decompressed with a dictionary is not valid UTF-8, meaning the dictionary shares the ID of the one the
+    /// text was compressed with but not its content.
+    Utf8(Utf8Error),
+    /// Any other error reported by the decoder.
+    Decoder(FrameDecoderError),
+}
+
+impl From<FrameDecoderError> for DecompressError {
+    fn from(value: FrameDecoderError) -> Self {
+        match value {
+            FrameDecoderError::ReadFrameHeaderError(err) => Self::ReadFrameHeader(err),
+            FrameDecoderError::FrameHeaderError(err)
+            | FrameDecoderError::FailedToInitialize(err) => Self::FrameHeader(err),
+            FrameDecoderError::FailedToReadBlockHeader(err) => Self::BlockHeader(err),
+            FrameDecoderError::FailedToReadBlockBody(err) => Self::BlockContent(err),
+            FrameDecoderError::DictNotProvided { dict_id } => {
+                Self::DictionaryRequired { id: dict_id }
+            }
+            err => Self::Decoder(err),
+        }
+    }
+}
+
+impl fmt::Display for DecompressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ReadFrameHeader(err) => write!(f, "failed to read frame header: {err}"),
+            Self::FrameHeader(err) => write!(f, "invalid frame header: {err}"),
+            Self::BlockHeader(err) => write!(f, "failed to read block header: {err}"),
+            Self::BlockContent(err) => write!(f, "failed to decode block: {err}"),
+            Self::BufferTooSmall { required, provided } => write!(
+                f,
+                "output buffer is too small, {required} bytes are required but only {provided} are available"
+            ),
+            Self::ChecksumMismatch {
+                expected,
+                calculated,
+            } => write!(
+                f,
+                "checksum mismatch, the frame stores {expected:#010x} but the data hashes to {calculated:#010x}"
+            ),
+            Self::ContentHashMismatch {
+                expected,
+                calculated,
+            } => {
+                f.write_str("content hash mismatch, expected ")?;
+                expected.iter().try_for_each(|byte| write!(f, "{byte:02x}"))?;
+                f.write_str(" but the data hashes to ")?;
+                calculated.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+            }
+            Self::DictionaryRequired { id } => {
+                write!(f, "the data was compressed with dictionary {id}, which was not provided")
+            }
+            Self::DictionaryMismatch { required, provided } => write!(
+                f,
+                "the data was compressed with dictionary {required}, but dictionary {provided} was provided"
+            ),
+            Self::Dictionary(err) => write!(f, "invalid dictionary: {err}"),
+            Self::SizeLimitExceeded { limit } => {
+                write!(f, "decompressed data exceeds the limit of {limit} bytes")
+            }
+            Self::TrailingBytes { len } => write!(f, "{len} bytes follow the frame"),
+            Self::Utf8(err) => write!(f, "decompressed text is not valid UTF-8: {err}"),
+            Self::Decoder(err) => write!(f, "failed to decompress: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecompressError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::ReadFrameHeader(err) => Some(err),
+            Self::FrameHeader(err) => Some(err),
+            Self::BlockHeader(err) => Some(err),
+            Self::BlockContent(err) => Some(err),
+            Self::BufferTooSmall { .. }
+            | Self::ChecksumMismatch { .. }
+            | Self::ContentHashMismatch { .. }
+            | Self::DictionaryRequired { .. }
+            | Self::DictionaryMismatch { .. }
+            | Self::SizeLimitExceeded { .. }
+            | Self::TrailingBytes { .. } => None,
+            Self::Dictionary(err) => Some(err),
+            Self::Utf8(err) => Some(err),
+            Self::Decoder(err) => Some(err),
+        }
+    }
+}
+
 /// # `include_zstd!`
 ///
 /// Compress and include a file at compile time using Zstd with the specified compression level and return an [`EmbeddedZstd`] struct.
 ///
+/// The compression level is optional, see [Default compression level](#default-compression-level).
+///
 /// ## Usage
 /// ```rust
 /// use include_zstd::{EmbeddedZstd, include_zstd};
 ///
 /// // Use `const` bindings to include the compressed data
-/// const COMPRESSED_DATA: EmbeddedZstd<4538> = include_zstd!("data/udhr_en.txt", 19);
+/// const COMPRESSED_DATA: EmbeddedZstd<4539> = include_zstd!("data/udhr_en.txt", 19);
 ///
 /// // It's also possible to use `let` bindings to eliminate the need for the type annotation
 /// let compressed_data = include_zstd!("data/udhr_en.txt", 19);
@@ -97,9 +545,342 @@ impl<const SIZE: usize> From<EmbeddedZstd<SIZE>> for Box<[u8]> {
 /// // Regardless of the method used, the representation of the compressed data is the same
 /// assert!(COMPRESSED_DATA == compressed_data);
 /// ```
+///
+/// ## Options
+/// The compression level can also be passed by name, followed by other `key = value` options:
+/// - `level = N`: compression level, equivalent to passing it positionally after the path.
+/// - `debug_level = N`: compression level used instead of `level` in debug builds, see
+///   [Debug builds](#debug-builds).
+///
+/// ```rust
+/// use include_zstd::{EmbeddedZstd, include_zstd};
+///
+/// const COMPRESSED_DATA: EmbeddedZstd<4539> = include_zstd!("data/udhr_en.txt", level = 19);
+/// assert!(COMPRESSED_DATA == include_zstd!("data/udhr_en.txt", 19));
+/// ```
+///
+/// Large or repetitive files can benefit from tuning the encoder beyond the level, with these options forwarded to
+/// Zstd's advanced parameters:
+/// - `window_log = N`: log2 of the largest distance a match may refer back to. The decoder supports windows up to
+///   100 MiB, so at most `26`. [`EmbeddedZstd::reader`] buffers up to a window of output, so a lower value bounds
+///   its memory usage.
+/// - `hash_log = N`, `chain_log = N`: log2 of the sizes of the match finder's tables.
+/// - `target_length = N`: match length the match finder aims for.
+/// - `strategy = "name"`: one of `"fast"`, `"dfast"`, `"greedy"`, `"lazy"`, `"lazy2"`, `"btlazy2"`, `"btopt"`,
+///   `"btultra"` and `"btultra2"`.
+/// - `long_distance_matching = true`: find matches far back in the input, past the reach of the regular match finder.
+/// - `enable_long = true`: like `zstd --long`, enables long distance matching with a window log of `26` unless
+///   `window_log` is given.
+///
+/// Values are checked against the ranges Zstd accepts. Parameters left out are derived from the level.
+///
+/// ```rust
+/// use include_zstd::include_zstd;
+///
+/// let compressed_data = include_zstd!("data/udhr_en.txt", level = 19, strategy = "btultra2", window_log = 20);
+/// assert_eq!(compressed_data.decompress().unwrap(), include_bytes!("../data/udhr_en.txt"));
+/// ```
+///
+/// Large files can be compressed on several threads with `workers = N`, which splits the input into jobs compressed in
+/// parallel. The output is the same for any number of workers, so builds stay reproducible, but differs from leaving
+/// the option out or setting it to `0`, which compresses on a single thread. Inputs smaller than one job, a few MiB at
+/// low levels and more at high levels, gain nothing from it.
+///
+/// ```rust
+/// use include_zstd::include_zstd;
+///
+/// let compressed_data = include_zstd!("data/udhr_en.txt", level = 19, workers = 4);
+/// assert!(compressed_data == include_zstd!("data/udhr_en.txt", level = 19, workers = 2));
+/// ```
+///
+/// With `checksum = true`, the frame stores a checksum of the original content, at a cost of 4 bytes. Decompressing
+/// then verifies the checksum, so embedded bytes altered after compilation, for example by patching the binary, are
+/// reported as [`DecompressError::ChecksumMismatch`] instead of yielding corrupted data.
+///
+/// ```rust
+/// use include_zstd::{EmbeddedZstd, include_zstd};
+///
+/// const COMPRESSED_DATA: EmbeddedZstd<4543> = include_zstd!("data/udhr_en.txt", level = 19, checksum = true);
+/// assert_eq!(COMPRESSED_DATA.decompress().unwrap(), include_bytes!("../data/udhr_en.txt"));
+/// ```
+///
+/// With `dict = "path"`, the file is compressed with the Zstd dictionary at `path`, relative to the directory
+/// containing the invoking crate's `Cargo.toml`, as produced by `zstd --train`. Small files similar to the samples the
+/// dictionary was trained on compress much better this way. The dictionary is not embedded along with the data: it is
+/// included once with [`include_zstd_dictionary!`] and passed to the `_with_dict` methods:
+/// [`EmbeddedZstd::decompress_with_dict`], [`EmbeddedZstd::decompress_into_with_dict`],
+/// [`EmbeddedZstd::decompress_array_with_dict`] and `verify_with_dict`, with the `verify` feature. The other methods,
+/// including [`EmbeddedZstd::reader`], as well as [`LazyZstd`], do not take a dictionary and fail with
+/// [`DecompressError::DictionaryRequired`].
+///
+/// ```rust
+/// use include_zstd::{EmbeddedZstdDict, include_zstd, include_zstd_dictionary};
+///
+/// const MESSAGES_DICT: EmbeddedZstdDict = include_zstd_dictionary!("fixtures/messages.dict");
+///
+/// let message = include_zstd!("fixtures/messages/00.json", 19, dict = "fixtures/messages.dict");
+/// assert_eq!(
+///     message.decompress_with_dict(&MESSAGES_DICT).unwrap(),
+///     include_bytes!("../fixtures/messages/00.json"),
+/// );
+/// ```
+///
+/// A dictionary trained at compile time by [`include_zstd_dictionary!`] is referenced with
+/// `dict_samples = "glob"` and `dict_size = N` instead, repeating the arguments given to it.
+///
+/// ## Default compression level
+/// The compression level can be left out, in which case the first of these applies:
+/// 1. The `INCLUDE_ZSTD_LEVEL` environment variable.
+/// 2. The `level` key of the `[package.metadata.include-zstd]` table in the invoking crate's `Cargo.toml`:
+///    ```toml
+///    [package.metadata.include-zstd]
+///    level = 19
+///    ```
+/// 3. Zstd's default compression level, `3`.
+///
+/// Changing either setting triggers a rebuild of the invoking crate.
+///
+/// ```rust
+/// use include_zstd::include_zstd;
+///
+/// let compressed_data = include_zstd!("data/udhr_en.txt");
+/// assert_eq!(compressed_data.decompress().unwrap(), include_bytes!("../data/udhr_en.txt"));
+/// ```
+///
+/// ## Debug builds
+/// High compression levels are slow on large files, so debug builds can use a faster level. In debug builds, the
+/// first of these applies before any of the settings above:
+/// 1. The `debug_level = N` option.
+/// 2. The `INCLUDE_ZSTD_DEBUG_LEVEL` environment variable.
+/// 3. The `debug-level` key of the `[package.metadata.include-zstd]` table:
+///    ```toml
+///    [package.metadata.include-zstd]
+///    level = 19
+///    debug-level = 1
+///    ```
+///
+/// Debug builds are detected from the `debug-assertions` setting of the build profile, which cargo also applies to
+/// the proc macro implementing this macro. Release builds ignore these settings, so their output is unchanged.
+///
+/// ```rust
+/// use include_zstd::include_zstd;
+///
+/// // Level 1 when built with debug assertions, level 19 otherwise
+/// let compressed_data = include_zstd!("data/udhr_en.txt", level = 19, debug_level = 1);
+/// assert_eq!(compressed_data.decompress().unwrap(), include_bytes!("../data/udhr_en.txt"));
+/// ```
+///
+/// ## Errors
+/// Failing to read or compress the file, as well as unknown or repeated options, is reported as a compile error
+/// pointing at the offending argument.
+/// ```rust,compile_fail
+/// use include_zstd::include_zstd;
+///
+/// // The file does not exist
+/// let compressed_data = include_zstd!("data/does_not_exist.txt", 19);
+/// ```
+/// ```rust,compile_fail
+/// use include_zstd::include_zstd;
+///
+/// // The compression level is out of range
+/// let compressed_data = include_zstd!("data/udhr_en.txt", 9000);
+/// ```
+/// ```rust,compile_fail
+/// use include_zstd::include_zstd;
+///
+/// // The option does not exist
+/// let compressed_data = include_zstd!("data/udhr_en.txt", level = 19, speed = 9000);
+/// ```
+/// ```rust,compile_fail
+/// use include_zstd::include_zstd;
+///
+/// // The compression level is given twice
+/// let compressed_data = include_zstd!("data/udhr_en.txt", 19, level = 19);
+/// ```
+/// ```rust,compile_fail
+/// use include_zstd::include_zstd;
+///
+/// // The window is larger than the decoder supports
+/// let compressed_data = include_zstd!("data/udhr_en.txt", 19, window_log = 27);
+/// ```
+/// ```rust,compile_fail
+/// use include_zstd::include_zstd;
+///
+/// // The strategy does not exist
+/// let compressed_data = include_zstd!("data/udhr_en.txt", 19, strategy = "fastest");
+/// ```
+/// ```rust,compile_fail
+/// use include_zstd::include_zstd;
+///
+/// // The file is not a dictionary
+/// let compressed_data = include_zstd!("data/udhr_en.txt", 19, dict = "data/not_utf8.bin");
+/// ```
 #[macro_export]
 macro_rules! include_zstd {
-    ($path:literal, $compression_level:literal) => {
-        $crate::include_zstd_macro::include_zstd_inner!($path, $compression_level)
+    ($path:literal $(, $($args:tt)+)?) => {
+        $crate::include_zstd_macro::include_zstd_inner!($path $(, $($args)+)?)
+    };
+}
+
+/// # `include_zstd_str!`
+///
+/// Compress and include a UTF-8 text file at compile time using Zstd with the specified compression level and return an
+/// [`EmbeddedZstdStr`] struct.
+///
+/// The file is validated as UTF-8 at compile time, so decompressing it yields a `String` without further checks.
+///
+/// Accepts the same options as [`include_zstd!`].
+///
+/// ## Usage
+/// ```rust
+/// use include_zstd::{EmbeddedZstdStr, include_zstd_str};
+///
+/// const COMPRESSED_TEXT: EmbeddedZstdStr<4539> = include_zstd_str!("data/udhr_en.txt", 19);
+///
+/// let text = COMPRESSED_TEXT.decompress().unwrap();
+/// assert_eq!(text, include_str!("../data/udhr_en.txt"));
+/// ```
+///
+/// ## Errors
+/// Files that are not valid UTF-8 are reported as a compile error pointing at the path.
+/// ```rust,compile_fail
+/// use include_zstd::include_zstd_str;
+///
+/// // The file is not valid UTF-8
+/// let compressed_text = include_zstd_str!("data/not_utf8.bin", 19);
+/// ```
+#[macro_export]
+macro_rules! include_zstd_str {
+    ($path:literal $(, $($args:tt)+)?) => {
+        $crate::include_zstd_macro::include_zstd_str_inner!($path $(, $($args)+)?)
+    };
+}
+
+/// # `include_zstd_dir!`
+///
+/// Compress and include every file below a directory at compile time using Zstd with the specified compression level
+/// and return an [`EmbeddedZstdDir`] struct.
+///
+/// Each file is compressed separately, so looking one up only decompresses that file. Symbolic links are followed.
+/// Cargo's target directory is skipped.
+///
+/// Besides the options of [`include_zstd!`], the files can be filtered with these options, matched against paths
+/// relative to the directory:
+/// - `include = ["glob", ...]`: only include files matching at least one of the globs.
+/// - `exclude = ["glob", ...]`: skip files matching any of the globs, even if they are included.
+/// - `gitignore = true`: skip files ignored by `.gitignore` files in the directory or its parents, and by
+///   `.git/info/exclude`. Defaults to `false`.
+///
+/// In globs, `*` does not match `/`, while `**/` matches any number of directories.
+///
+/// With `solid = true`, the files are instead compressed together into a single frame and an [`EmbeddedZstdArchive`]
+/// is returned. This compresses many small, similar files much better, at the cost of decompressing every file at
+/// once.
+///
+/// Changes to the included files trigger a rebuild, but files added to or removed from the directory are only
+/// picked up once the invoking crate is rebuilt for another reason.
+///
+/// ## Usage
+/// ```rust
+/// use include_zstd::{EmbeddedZstdDir, include_zstd_dir};
+///
+/// const DATA_DIR: EmbeddedZstdDir = include_zstd_dir!("data", 19);
+///
+/// let paths: Vec<&str> = DATA_DIR.iter().map(|(path, _)| path).collect();
+/// assert_eq!(paths, ["not_utf8.bin", "udhr_en.txt"]);
+///
+/// // Only include text files
+/// const TEXT_DIR: EmbeddedZstdDir = include_zstd_dir!("data", 19, include = ["**/*.txt"]);
+/// assert_eq!(TEXT_DIR.len(), 1);
+/// assert!(TEXT_DIR.contains("udhr_en.txt"));
+///
+/// // Skip binary files
+/// const NON_BINARY_DIR: EmbeddedZstdDir = include_zstd_dir!("data", 19, exclude = ["*.bin"]);
+/// assert_eq!(NON_BINARY_DIR.len(), 1);
+/// assert!(!NON_BINARY_DIR.contains("not_utf8.bin"));
+/// ```
+///
+/// ## Errors
+/// Unknown or repeated options are reported as a compile error.
+/// ```rust,compile_fail
+/// use include_zstd::include_zstd_dir;
+///
+/// let dir = include_zstd_dir!("data", 19, include = ["*.txt"], include = ["*.bin"]);
+/// ```
+#[macro_export]
+macro_rules! include_zstd_dir {
+    ($path:literal $(, $($args:tt)+)?) => {
+        $crate::include_zstd_macro::include_zstd_dir_inner!($path $(, $($args)+)?)
+    };
+}
+
+/// # `include_zstd_dictionary!`
+///
+/// Include a Zstd dictionary at compile time and return an [`EmbeddedZstdDict`] struct, for decompressing data
+/// compressed with the `dict = "path"` option of [`include_zstd!`].
+///
+/// The dictionary must be in the format produced by `zstd --train`, with a nonzero dictionary ID. It is included as
+/// is, without compression, since it is needed to decompress anything else.
+///
+/// Instead of a path, the dictionary can be trained during compilation with `samples = "glob"`, on the files matching
+/// the glob relative to the directory containing the invoking crate's `Cargo.toml`, like `zstd --train`. Files in
+/// hidden directories and cargo's target directory never match. The
+/// dictionary holds at most `size = N` bytes, defaulting to 110 KiB. Training is reproducible, so [`include_zstd!`]
+/// compresses with the same dictionary when given `dict_samples` and `dict_size` with the same values. Trained
+/// dictionaries are stored in the [compression cache](crate#compression-cache). Changes to the samples trigger a
+/// rebuild, but files that start matching the glob are only picked up once the invoking crate is rebuilt for another
+/// reason.
+///
+/// ## Usage
+/// ```rust
+/// use include_zstd::{EmbeddedZstdDict, include_zstd, include_zstd_dictionary};
+///
+/// const MESSAGES_DICT: EmbeddedZstdDict = include_zstd_dictionary!("fixtures/messages.dict");
+///
+/// let message = include_zstd!("fixtures/messages/01.json", 19, dict = "fixtures/messages.dict");
+/// assert_eq!(
+///     message.decompress_with_dict(&MESSAGES_DICT).unwrap(),
+///     include_bytes!("../fixtures/messages/01.json"),
+/// );
+///
+/// // Trained on the messages during compilation
+/// const TRAINED_DICT: EmbeddedZstdDict = include_zstd_dictionary!(samples = "fixtures/messages/*.json", size = 2048);
+///
+/// let message = include_zstd!(
+///     "fixtures/messages/01.json",
+///     19,
+///     dict_samples = "fixtures/messages/*.json",
+///     dict_size = 2048,
+/// );
+/// assert_eq!(
+///     message.decompress_with_dict(&TRAINED_DICT).unwrap(),
+///     include_bytes!("../fixtures/messages/01.json"),
+/// );
+/// ```
+///
+/// ## Errors
+/// Failing to read the file, or files that are not dictionaries, are reported as a compile error pointing at the path.
+/// ```rust,compile_fail
+/// use include_zstd::include_zstd_dictionary;
+///
+/// // The file is not a dictionary
+/// let dict = include_zstd_dictionary!("data/udhr_en.txt");
+/// ```
+/// ```rust,compile_fail
+/// use include_zstd::include_zstd_dictionary;
+///
+/// // Dictionaries are not compressed
+/// let dict = include_zstd_dictionary!("fixtures/messages.dict", 19);
+/// ```
+/// ```rust,compile_fail
+/// use include_zstd::include_zstd_dictionary;
+///
+/// // No files match the samples
+/// let dict = include_zstd_dictionary!(samples = "fixtures/**/*.yaml");
+/// ```
+#[macro_export]
+macro_rules! include_zstd_dictionary {
+    ($($args:tt)+) => {
+        $crate::include_zstd_macro::include_zstd_dictionary_inner!($($args)+)
     };
 }
@@ -0,0 +1,239 @@
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+use ruzstd::StreamingDecoder;
+use ruzstd::{frame_decoder::FrameDecoderError, FrameDecoder};
+
+use crate::{DecompressError, EmbeddedZstdDict};
+
+/// # `EmbeddedZstdSlice`
+/// Borrowed view of Zstd-compressed data, without the compressed size in its type.
+///
+/// Lets compressed data of different sizes be stored side by side, for example in an
+/// [`EmbeddedZstdDir`](crate::EmbeddedZstdDir). Use [`EmbeddedZstd::as_slice`](crate::EmbeddedZstd::as_slice) to get one
+/// from an [`EmbeddedZstd`](crate::EmbeddedZstd).
+///
+/// ## Usage
+/// ```rust
+/// use include_zstd::{EmbeddedZstd, EmbeddedZstdSlice, include_zstd};
+///
+/// const COMPRESSED_DATA: EmbeddedZstd<4539> = include_zstd!("data/udhr_en.txt", 19);
+///
+/// let slice: EmbeddedZstdSlice = COMPRESSED_DATA.as_slice();
+/// assert_eq!(slice.size(), COMPRESSED_DATA.size());
+/// assert_eq!(slice.decompress().unwrap(), COMPRESSED_DATA.decompress().unwrap());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EmbeddedZstdSlice<'a> {
+    data: &'a [u8],
+    decompressed_size: usize,
+    content_hash: &'a [u8; 32],
+}
+
+impl<'a> EmbeddedZstdSlice<'a> {
+    #[doc(hidden)]
+    #[must_use]
+    pub const unsafe fn new_unchecked(
+        data: &'a [u8],
+        decompressed_size: usize,
+        content_hash: &'a [u8; 32],
+    ) -> Self {
+        Self {
+            data,
+            decompressed_size,
+            content_hash,
+        }
+    }
+
+    /// Returns the size of the compressed data, in bytes.
+    pub const fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the size of the decompressed data, in bytes.
+    pub const fn decompressed_size(&self) -> usize {
+        self.decompressed_size
+    }
+
+    /// Returns the SHA-256 hash of the decompressed data, see
+    /// [`EmbeddedZstd::content_hash`](crate::EmbeddedZstd::content_hash).
+    pub const fn content_hash(&self) -> [u8; 32] {
+        *self.content_hash
+    }
+
+    /// Decompress the data and return it as a `Vec<u8>`, reporting corrupted data as a [`DecompressError`].
+    pub fn decompress(&self) -> Result<Vec<u8>, DecompressError> {
+        self.decompress_with(FrameDecoder::new())
+    }
+
+    /// Decompress data compressed with a dictionary, see
+    /// [`EmbeddedZstd::decompress_with_dict`](crate::EmbeddedZstd::decompress_with_dict).
+    pub fn decompress_with_dict(
+        &self,
+        dict: &EmbeddedZstdDict,
+    ) -> Result<Vec<u8>, DecompressError> {
+        self.decompress_with(dict.decoder(self.data)?)
+    }
+
+    fn decompress_with(&self, mut decoder: FrameDecoder) -> Result<Vec<u8>, DecompressError> {
+        let mut buf = Vec::with_capacity(self.decompressed_size);
+
+        decoder.decode_all_to_vec(self.data, &mut buf)?;
+        verify_checksum(&decoder)?;
+        Ok(buf)
+    }
+
+    /// Decompress the data into `out`, returning the number of bytes written.
+    ///
+    /// See [`EmbeddedZstd::decompress_into`](crate::EmbeddedZstd::decompress_into).
+    pub fn decompress_into(&self, out: &mut [u8]) -> Result<usize, DecompressError> {
+        self.decompress_into_with(FrameDecoder::new(), out)
+    }
+
+    /// Decompress data compressed with a dictionary into `out`, see
+    /// [`EmbeddedZstd::decompress_into_with_dict`](crate::EmbeddedZstd::decompress_into_with_dict).
+    pub fn decompress_into_with_dict(
+        &self,
+        dict: &EmbeddedZstdDict,
+        out: &mut [u8],
+    ) -> Result<usize, DecompressError> {
+        self.decompress_into_with(dict.decoder(self.data)?, out)
+    }
+
+    fn decompress_into_with(
+        &self,
+        mut decoder: FrameDecoder,
+        out: &mut [u8],
+    ) -> Result<usize, DecompressError> {
+        if out.len() < self.decompressed_size {
+            return Err(DecompressError::BufferTooSmall {
+                required: self.decompressed_size,
+                provided: out.len(),
+            });
+        }
+
+        let written = decoder
+            .decode_all(self.data, out)
+            .map_err(|err| match err {
+                FrameDecoderError::TargetTooSmall => DecompressError::BufferTooSmall {
+                    required: self.decompressed_size,
+                    provided: out.len(),
+                },
+                err => err.into(),
+            })?;
+        verify_checksum(&decoder)?;
+        Ok(written)
+    }
+
+    /// Decompress the data into a fixed-size array.
+    ///
+    /// See [`EmbeddedZstd::decompress_array`](crate::EmbeddedZstd::decompress_array).
+    pub fn decompress_array<const N: usize>(&self) -> Result<[u8; N], DecompressError> {
+        let mut out = [0; N];
+
+        self.decompress_into(&mut out)?;
+        Ok(out)
+    }
+
+    /// Decompress data compressed with a dictionary into a fixed-size array, see
+    /// [`EmbeddedZstd::decompress_array_with_dict`](crate::EmbeddedZstd::decompress_array_with_dict).
+    pub fn decompress_array_with_dict<const N: usize>(
+        &self,
+        dict: &EmbeddedZstdDict,
+    ) -> Result<[u8; N], DecompressError> {
+        let mut out = [0; N];
+
+        self.decompress_into_with_dict(dict, &mut out)?;
+        Ok(out)
+    }
+
+    /// Decompress the data and check it against its [`content_hash`](Self::content_hash).
+    ///
+    /// See [`EmbeddedZstd::verify`](crate::EmbeddedZstd::verify).
+    #[cfg(feature = "verify")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "verify")))]
+    pub fn verify(&self) -> Result<(), DecompressError> {
+        self.check_content_hash(&self.decompress()?)
+    }
+
+    /// Decompress data compressed with a dictionary and check it against its [`content_hash`](Self::content_hash).
+    ///
+    /// See [`EmbeddedZstd::verify_with_dict`](crate::EmbeddedZstd::verify_with_dict).
+    #[cfg(feature = "verify")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "verify")))]
+    pub fn verify_with_dict(&self, dict: &EmbeddedZstdDict) -> Result<(), DecompressError> {
+        self.check_content_hash(&self.decompress_with_dict(dict)?)
+    }
+
+    #[cfg(feature = "verify")]
+    fn check_content_hash(&self, decompressed: &[u8]) -> Result<(), DecompressError> {
+        use sha2::{Digest, Sha256};
+
+        let calculated: [u8; 32] = Sha256::digest(decompressed).into();
+        if calculated != *self.content_hash {
+            return Err(DecompressError::ContentHashMismatch {
+                expected: *self.content_hash,
+                calculated,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns a reader that decompresses the data incrementally, buffering up to a window of output, see
+    /// [`EmbeddedZstd::reader`](crate::EmbeddedZstd::reader).
+    ///
+    /// If the frame has a content checksum, it is verified once the end of the data is reached, and a mismatch is
+    /// reported as an [`std::io::ErrorKind::InvalidData`] error wrapping [`DecompressError::ChecksumMismatch`].
+    ///
+    /// Only available with the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn reader(&self) -> Result<impl std::io::BufRead + 'a, DecompressError> {
+        let decoder = StreamingDecoder::new(self.data)?;
+
+        Ok(std::io::BufReader::new(VerifyingReader(decoder)))
+    }
+}
+
+/// Checks the data `decoder` decoded against the content checksum of the frame, if it has one.
+pub(crate) fn verify_checksum(decoder: &FrameDecoder) -> Result<(), DecompressError> {
+    match (
+        decoder.get_checksum_from_data(),
+        decoder.get_calculated_checksum(),
+    ) {
+        (Some(expected), Some(calculated)) if expected != calculated => {
+            Err(DecompressError::ChecksumMismatch {
+                expected,
+                calculated,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Streaming decoder that verifies the content checksum once the data is exhausted.
+#[cfg(feature = "std")]
+struct VerifyingReader<'a>(StreamingDecoder<&'a [u8], FrameDecoder>);
+
+#[cfg(feature = "std")]
+impl std::io::Read for VerifyingReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.0.read(buf)?;
+        if read == 0 && !buf.is_empty() {
+            verify_checksum(&self.0.decoder)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        }
+
+        Ok(read)
+    }
+}
+
+impl From<EmbeddedZstdSlice<'_>> for Vec<u8> {
+    /// Decompress the data and return it as a `Vec<u8>`
+    ///
+    /// ## Panics
+    /// Panics if the data is corrupted, see [`EmbeddedZstdSlice::decompress`] for a fallible alternative.
+    fn from(value: EmbeddedZstdSlice<'_>) -> Self {
+        value.decompress().unwrap()
+    }
+}
@@ -0,0 +1,95 @@
+use alloc::{boxed::Box, string::String};
+
+use crate::{DecompressError, EmbeddedZstd, EmbeddedZstdDict};
+
+/// # `EmbeddedZstdStr`
+/// Opaque struct that holds Zstd-compressed UTF-8 text.
+///
+/// The text is validated when it is embedded, so decompressing it does not validate it again.
+///
+/// See [`include_zstd_str!`](crate::include_zstd_str) for information on how to create an instance of this struct.
+///
+/// ## Usage
+/// ```rust
+/// use include_zstd::{EmbeddedZstdStr, include_zstd_str};
+///
+/// const COMPRESSED_TEXT: EmbeddedZstdStr<4539> = include_zstd_str!("data/udhr_en.txt", 19);
+///
+/// // The compressed text can be converted into a `String` or a `Box<str>`
+/// let text: String = COMPRESSED_TEXT.into();
+/// let boxed_text: Box<str> = COMPRESSED_TEXT.into();
+/// assert!(text.starts_with("Universal Declaration of Human Rights"));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EmbeddedZstdStr<const SIZE: usize>(EmbeddedZstd<SIZE>);
+
+impl<const SIZE: usize> EmbeddedZstdStr<SIZE> {
+    #[doc(hidden)]
+    #[must_use]
+    pub const unsafe fn new_unchecked(
+        data: [u8; SIZE],
+        decompressed_size: usize,
+        content_hash: [u8; 32],
+    ) -> Self {
+        Self(EmbeddedZstd::new_unchecked(
+            data,
+            decompressed_size,
+            content_hash,
+        ))
+    }
+
+    /// Returns the compressed data.
+    pub const fn compressed(&self) -> &EmbeddedZstd<SIZE> {
+        &self.0
+    }
+
+    /// Returns the size of the compressed text, in bytes.
+    pub const fn size(&self) -> usize {
+        SIZE
+    }
+
+    /// Returns the size of the decompressed text, in bytes.
+    pub const fn decompressed_size(&self) -> usize {
+        self.0.decompressed_size()
+    }
+
+    /// Returns the SHA-256 hash of the decompressed text, see [`EmbeddedZstd::content_hash`].
+    pub const fn content_hash(&self) -> [u8; 32] {
+        self.0.content_hash()
+    }
+
+    /// Decompress the text and return it as a `String`, reporting corrupted data as a [`DecompressError`].
+    pub fn decompress(&self) -> Result<String, DecompressError> {
+        let bytes = self.0.decompress()?;
+
+        // SAFETY: the text was validated as UTF-8 when it was embedded
+        Ok(unsafe { String::from_utf8_unchecked(bytes) })
+    }
+
+    /// Decompress text compressed with a dictionary, see [`EmbeddedZstd::decompress_with_dict`].
+    ///
+    /// Dictionaries are only told apart by their ID, so the text is validated as UTF-8 again: another dictionary
+    /// reusing the ID decodes it into arbitrary bytes, reported as [`DecompressError::Utf8`].
+    pub fn decompress_with_dict(&self, dict: &EmbeddedZstdDict) -> Result<String, DecompressError> {
+        let bytes = self.0.decompress_with_dict(dict)?;
+
+        String::from_utf8(bytes).map_err(|err| DecompressError::Utf8(err.utf8_error()))
+    }
+}
+
+impl<const SIZE: usize> From<EmbeddedZstdStr<SIZE>> for String {
+    /// Decompress the text and return it as a `String`
+    ///
+    /// ## Panics
+    /// Panics if the data is corrupted, see [`EmbeddedZstdStr::decompress`] for a fallible alternative.
+    fn from(value: EmbeddedZstdStr<SIZE>) -> Self {
+        value.decompress().unwrap()
+    }
+}
+
+impl<const SIZE: usize> From<EmbeddedZstdStr<SIZE>> for Box<str> {
+    /// Decompress the text and return it as a `Box<str>`
+    fn from(value: EmbeddedZstdStr<SIZE>) -> Self {
+        String::from(value).into_boxed_str()
+    }
+}
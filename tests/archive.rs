@@ -0,0 +1,20 @@
+use include_zstd::include_zstd_dir;
+
+#[test]
+fn solid_archive_shares_redundancy_across_files() {
+    let archive = include_zstd_dir!("fixtures/schemas", 19, solid = true);
+    assert!(archive.size() < archive.separate_size());
+    assert_eq!(archive.len(), 50);
+
+    let data = archive.decompress().unwrap();
+    for (i, (path, contents)) in data.iter().enumerate() {
+        assert_eq!(path, format!("schema_{i:02}.json"));
+        assert_eq!(
+            contents,
+            format!(
+                r#"{{"$schema": "https://json-schema.org/draft/2020-12/schema", "title": "Schema {i}", "type": "object", "properties": {{"id": {{"type": "integer"}}, "name": {{"type": "string"}}}}}}"#
+            )
+            .as_bytes()
+        );
+    }
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use std::{collections::HashSet, fs, path::Path};
+
+fn run_fixture(dir: &Path, envs: &[(&str, &str)]) -> String {
+    String::from_utf8(common::cargo(dir, &["run", "--quiet"], envs).stdout).unwrap()
+}
+
+fn entries(cache_dir: &Path) -> HashSet<String> {
+    fs::read_dir(cache_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect()
+}
+
+#[test]
+fn reuses_cached_compressed_output() {
+    const MAIN: &str = r#"fn main() {
+    let data: Vec<u8> = include_zstd::include_zstd!("asset.txt", 3).into();
+    print!("{}", String::from_utf8(data).unwrap());
+}
+"#;
+
+    let dir = common::fixture("cache-fixture", "", &[("src/main.rs", MAIN)]);
+    let cache_dir = dir.join("cache");
+    let envs = [("INCLUDE_ZSTD_CACHE_DIR", cache_dir.to_str().unwrap())];
+
+    common::write(&dir, "asset.txt", "first!");
+    assert_eq!(run_fixture(&dir, &envs), "first!");
+    let first = entries(&cache_dir);
+    assert_eq!(first.len(), 1);
+
+    // Changed content is stored under a new key
+    common::write(&dir, "asset.txt", "second");
+    assert_eq!(run_fixture(&dir, &envs), "second");
+    let second = entries(&cache_dir);
+    assert_eq!(second.len(), 2);
+
+    // Planting the first output under the second key shows the expansion is served from the cache
+    let first_entry = cache_dir.join(first.iter().next().unwrap());
+    let second_entry = cache_dir.join(second.difference(&first).next().unwrap());
+    fs::copy(first_entry, second_entry).unwrap();
+    common::write(&dir, "src/main.rs", MAIN);
+    assert_eq!(run_fixture(&dir, &envs), "first!");
+
+    // An empty directory disables the cache
+    common::write(&dir, "src/main.rs", MAIN);
+    assert_eq!(
+        run_fixture(&dir, &[("INCLUDE_ZSTD_CACHE_DIR", "")]),
+        "second"
+    );
+}
+
+#[test]
+fn caches_in_target_dir_by_default() {
+    let dir = common::fixture(
+        "cache-default-fixture",
+        "",
+        &[
+            ("asset.txt", "cached by default"),
+            (
+                "src/main.rs",
+                r#"fn main() {
+    let data: Vec<u8> = include_zstd::include_zstd!("asset.txt", 3).into();
+    print!("{}", String::from_utf8(data).unwrap());
+}
+"#,
+            ),
+        ],
+    );
+
+    assert_eq!(run_fixture(&dir, &[]), "cached by default");
+
+    let cache_dir =
+        Path::new(env!("CARGO_TARGET_TMPDIR")).join("fixture-target/debug/include-zstd-cache");
+    assert!(!entries(&cache_dir).is_empty());
+}
@@ -0,0 +1,62 @@
+use std::io::Write;
+
+use include_zstd::{DecompressError, EmbeddedZstdSlice};
+
+const CONTENT: &[u8] = include_bytes!("../data/udhr_en.txt");
+
+fn frame_with_checksum() -> Vec<u8> {
+    let mut encoder = zstd::Encoder::new(Vec::new(), 3).unwrap();
+    encoder.include_checksum(true).unwrap();
+    encoder.write_all(CONTENT).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn assert_mismatch(result: Result<impl Sized, DecompressError>) {
+    assert!(matches!(
+        result,
+        Err(DecompressError::ChecksumMismatch { .. })
+    ));
+}
+
+#[test]
+fn verifies_content_checksum() {
+    let frame = frame_with_checksum();
+    let slice = unsafe { EmbeddedZstdSlice::new_unchecked(&frame, CONTENT.len(), &[0; 32]) };
+
+    let mut out = vec![0; CONTENT.len()];
+    assert_eq!(slice.decompress().unwrap(), CONTENT);
+    assert_eq!(slice.decompress_into(&mut out).unwrap(), CONTENT.len());
+    #[cfg(feature = "std")]
+    {
+        use std::io::Read;
+
+        let mut read = Vec::new();
+        slice.reader().unwrap().read_to_end(&mut read).unwrap();
+        assert_eq!(read, CONTENT);
+    }
+
+    // The checksum is stored in the last 4 bytes of the frame
+    let mut corrupted = frame.clone();
+    *corrupted.last_mut().unwrap() ^= 1;
+    let slice = unsafe { EmbeddedZstdSlice::new_unchecked(&corrupted, CONTENT.len(), &[0; 32]) };
+
+    assert_mismatch(slice.decompress());
+    assert_mismatch(slice.decompress_into(&mut out));
+    #[cfg(feature = "std")]
+    {
+        use std::io::{ErrorKind, Read};
+
+        let err = slice
+            .reader()
+            .unwrap()
+            .read_to_end(&mut Vec::new())
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_mismatch(Err::<(), _>(
+            *err.into_inner()
+                .unwrap()
+                .downcast::<DecompressError>()
+                .unwrap(),
+        ));
+    }
+}
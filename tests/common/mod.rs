@@ -0,0 +1,69 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+/// Creates a fresh crate named `name` that depends on `include-zstd` with the given dependency options, and writes
+/// `files` (paths relative to the crate root) into it.
+pub fn fixture(name: &str, dependency_options: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    fs::write(
+        dir.join("Cargo.toml"),
+        format!(
+            r#"[package]
+name = "{name}"
+version = "0.0.0"
+edition = "2021"
+
+[dependencies]
+include-zstd = {{ path = {:?}{dependency_options} }}
+
+[workspace]
+"#,
+            env!("CARGO_MANIFEST_DIR"),
+        ),
+    )
+    .unwrap();
+
+    for (path, contents) in files {
+        write(&dir, path, contents);
+    }
+
+    dir
+}
+
+/// Writes `contents` to `path`, relative to the fixture crate root.
+pub fn write(dir: &Path, path: &str, contents: &str) {
+    let path = dir.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+/// Runs `cargo` with `args` and the environment variables `envs` in the fixture crate, sharing one target directory
+/// between fixtures.
+pub fn cargo(dir: &Path, args: &[&str], envs: &[(&str, &str)]) -> Output {
+    let output = Command::new(env!("CARGO"))
+        .args(args)
+        .env_remove("INCLUDE_ZSTD_LEVEL")
+        .env_remove("INCLUDE_ZSTD_DEBUG_LEVEL")
+        .env_remove("INCLUDE_ZSTD_CACHE_DIR")
+        .envs(envs.iter().copied())
+        .current_dir(dir)
+        .env(
+            "CARGO_TARGET_DIR",
+            Path::new(env!("CARGO_TARGET_TMPDIR")).join("fixture-target"),
+        )
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    output
+}
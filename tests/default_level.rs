@@ -0,0 +1,101 @@
+mod common;
+
+use std::{fs, path::Path};
+
+fn run_fixture(dir: &Path, envs: &[(&str, &str)]) -> String {
+    String::from_utf8(common::cargo(dir, &["run", "--quiet"], envs).stdout).unwrap()
+}
+
+#[test]
+fn resolves_default_level_by_precedence() {
+    let dir = common::fixture(
+        "default-level-fixture",
+        "",
+        &[
+            ("asset.txt", include_str!("../data/udhr_en.txt")),
+            (
+                "src/main.rs",
+                r#"fn main() {
+    let default = include_zstd::include_zstd!("asset.txt").size();
+    let levels = [
+        (1, include_zstd::include_zstd!("asset.txt", 1).size()),
+        (3, include_zstd::include_zstd!("asset.txt", 3).size()),
+        (19, include_zstd::include_zstd!("asset.txt", 19).size()),
+    ];
+
+    let (level, _) = levels.iter().find(|(_, size)| *size == default).unwrap();
+    print!("{level}");
+}
+"#,
+            ),
+        ],
+    );
+
+    // Zstd's default applies without any configuration
+    assert_eq!(run_fixture(&dir, &[]), "3");
+
+    // The manifest overrides Zstd's default
+    let manifest_path = dir.join("Cargo.toml");
+    let manifest = fs::read_to_string(&manifest_path).unwrap();
+    fs::write(
+        &manifest_path,
+        manifest + "\n[package.metadata.include-zstd]\nlevel = 19\n",
+    )
+    .unwrap();
+    assert_eq!(run_fixture(&dir, &[]), "19");
+
+    // The environment variable overrides the manifest
+    assert_eq!(run_fixture(&dir, &[("INCLUDE_ZSTD_LEVEL", "1")]), "1");
+}
+
+#[test]
+fn resolves_debug_level_from_profile() {
+    let dir = common::fixture(
+        "debug-level-fixture",
+        "",
+        &[
+            ("asset.txt", include_str!("../data/udhr_en.txt")),
+            (
+                "src/main.rs",
+                r#"fn main() {
+    // `debug_level` pins the reference levels in every profile
+    let levels = [
+        (1, include_zstd::include_zstd!("asset.txt", 1, debug_level = 1).size()),
+        (3, include_zstd::include_zstd!("asset.txt", 3, debug_level = 3).size()),
+        (19, include_zstd::include_zstd!("asset.txt", 19, debug_level = 19).size()),
+    ];
+    let level = |size| levels.iter().find(|(_, s)| *s == size).unwrap().0;
+
+    let option = include_zstd::include_zstd!("asset.txt", level = 19, debug_level = 1).size();
+    let configured = include_zstd::include_zstd!("asset.txt", 19).size();
+    print!("{} {}", level(option), level(configured));
+}
+"#,
+            ),
+        ],
+    );
+    let release = |envs| {
+        let args = ["run", "--quiet", "--release"];
+        String::from_utf8(common::cargo(&dir, &args, envs).stdout).unwrap()
+    };
+
+    // Only the option applies without any configuration
+    assert_eq!(run_fixture(&dir, &[]), "1 19");
+    assert_eq!(release(&[]), "19 19");
+
+    // The manifest lowers every level in debug builds
+    let manifest_path = dir.join("Cargo.toml");
+    let manifest = fs::read_to_string(&manifest_path).unwrap();
+    fs::write(
+        &manifest_path,
+        manifest + "\n[package.metadata.include-zstd]\ndebug-level = 3\n",
+    )
+    .unwrap();
+    assert_eq!(run_fixture(&dir, &[]), "1 3");
+    assert_eq!(release(&[]), "19 19");
+
+    // The environment variable overrides the manifest
+    let envs = [("INCLUDE_ZSTD_DEBUG_LEVEL", "1")];
+    assert_eq!(run_fixture(&dir, &envs), "1 1");
+    assert_eq!(release(&envs), "19 19");
+}
@@ -0,0 +1,200 @@
+use include_zstd::{
+    include_zstd, include_zstd_dictionary, include_zstd_dir, include_zstd_str, DecompressError,
+    EmbeddedZstdDict,
+};
+
+const MESSAGES_DICT: EmbeddedZstdDict = include_zstd_dictionary!("fixtures/messages.dict");
+
+#[test]
+fn decompresses_with_dictionary() {
+    assert_eq!(
+        MESSAGES_DICT.as_bytes(),
+        include_bytes!("../fixtures/messages.dict")
+    );
+    assert_eq!(MESSAGES_DICT.id(), 528_767_346);
+
+    let message = include_zstd!(
+        "fixtures/messages/03.json",
+        19,
+        dict = "fixtures/messages.dict"
+    );
+    let plain = include_zstd!("fixtures/messages/03.json", 19);
+    assert!(message.size() < plain.size());
+    assert_eq!(
+        message.decompress_with_dict(&MESSAGES_DICT).unwrap(),
+        include_bytes!("../fixtures/messages/03.json"),
+    );
+
+    let text = include_zstd_str!(
+        "fixtures/messages/04.json",
+        19,
+        dict = "fixtures/messages.dict"
+    );
+    assert_eq!(
+        text.decompress_with_dict(&MESSAGES_DICT).unwrap(),
+        include_str!("../fixtures/messages/04.json"),
+    );
+
+    let dir = include_zstd_dir!("fixtures/messages", 19, dict = "fixtures/messages.dict");
+    assert_eq!(dir.len(), 64);
+    for (path, file) in dir.iter() {
+        let expected = std::fs::read(format!("fixtures/messages/{path}")).unwrap();
+        assert_eq!(file.decompress_with_dict(&MESSAGES_DICT).unwrap(), expected);
+    }
+}
+
+#[test]
+fn decompresses_with_dictionary_without_allocating_output() {
+    const MESSAGE: &[u8] = include_bytes!("../fixtures/messages/07.json");
+
+    let message = include_zstd!(
+        "fixtures/messages/07.json",
+        19,
+        dict = "fixtures/messages.dict"
+    );
+    let mut buf = [0; 1024];
+    let len = message
+        .decompress_into_with_dict(&MESSAGES_DICT, &mut buf)
+        .unwrap();
+    assert_eq!(&buf[..len], MESSAGE);
+    assert!(matches!(
+        message.decompress_into(&mut buf),
+        Err(DecompressError::DictionaryRequired { .. })
+    ));
+
+    let array = message
+        .decompress_array_with_dict::<{ MESSAGE.len() }>(&MESSAGES_DICT)
+        .unwrap();
+    assert_eq!(array, MESSAGE);
+}
+
+#[test]
+fn reports_dictionary_errors() {
+    let message = include_zstd!(
+        "fixtures/messages/05.json",
+        19,
+        dict = "fixtures/messages.dict"
+    );
+    assert!(matches!(
+        message.decompress(),
+        Err(DecompressError::DictionaryRequired { id }) if id == MESSAGES_DICT.id()
+    ));
+
+    let other = unsafe { EmbeddedZstdDict::new_unchecked(MESSAGES_DICT.as_bytes(), 1) };
+    assert!(matches!(
+        message.decompress_with_dict(&other),
+        Err(DecompressError::DictionaryMismatch { required, provided: 1 }) if required == MESSAGES_DICT.id()
+    ));
+}
+
+#[test]
+fn trains_dictionary_from_samples() {
+    const TRAINED_DICT: EmbeddedZstdDict =
+        include_zstd_dictionary!(samples = "fixtures/messages/*.json", size = 2048);
+    assert!(TRAINED_DICT.size() <= 2048);
+
+    // Referencing the same samples and size trains the same dictionary
+    let dir = include_zstd_dir!(
+        "fixtures/messages",
+        19,
+        dict_samples = "fixtures/messages/*.json",
+        dict_size = 2048,
+    );
+    let plain = include_zstd_dir!("fixtures/messages", 19);
+    let (mut trained_size, mut plain_size) = (0, 0);
+    for ((path, file), (_, plain_file)) in dir.iter().zip(plain.iter()) {
+        let expected = std::fs::read(format!("fixtures/messages/{path}")).unwrap();
+        assert_eq!(file.decompress_with_dict(&TRAINED_DICT).unwrap(), expected);
+        trained_size += file.size();
+        plain_size += plain_file.size();
+    }
+    assert!(trained_size < plain_size);
+}
+
+#[test]
+fn decompresses_runtime_frames() {
+    // Frames without a content size or with a checksum, as other implementations may produce them
+    let message = include_bytes!("../fixtures/messages/06.json");
+    let mut compressor =
+        zstd::bulk::Compressor::with_dictionary(19, MESSAGES_DICT.as_bytes()).unwrap();
+    compressor.include_contentsize(false).unwrap();
+    compressor.include_checksum(true).unwrap();
+    let mut frame = compressor.compress(message).unwrap();
+    assert_eq!(MESSAGES_DICT.decompress(&frame, 1024).unwrap(), message);
+
+    let checksum = frame.len() - 1;
+    frame[checksum] ^= 0xFF;
+    assert!(matches!(
+        MESSAGES_DICT.decompress(&frame, 1024),
+        Err(DecompressError::ChecksumMismatch { .. })
+    ));
+
+    let other = unsafe { EmbeddedZstdDict::new_unchecked(MESSAGES_DICT.as_bytes(), 1) };
+    assert!(matches!(
+        other.decompress(&frame, 1024),
+        Err(DecompressError::DictionaryMismatch { provided: 1, .. })
+    ));
+}
+
+#[test]
+fn limits_runtime_frames() {
+    // Zeros expand about 10000 times, far beyond the limit
+    let data = vec![0; 4 << 20];
+    let frame = zstd::bulk::Compressor::with_dictionary(3, MESSAGES_DICT.as_bytes())
+        .unwrap()
+        .compress(&data)
+        .unwrap();
+    assert!(matches!(
+        MESSAGES_DICT.decompress(&frame, 1 << 20),
+        Err(DecompressError::SizeLimitExceeded { limit }) if limit == 1 << 20
+    ));
+
+    // Without a content size, only the decoded output reveals the size
+    let mut frame = Vec::new();
+    let mut encoder =
+        zstd::Encoder::with_dictionary(&mut frame, 3, MESSAGES_DICT.as_bytes()).unwrap();
+    encoder.include_contentsize(false).unwrap();
+    encoder.window_log(17).unwrap();
+    std::io::Write::write_all(&mut encoder, &data).unwrap();
+    encoder.finish().unwrap();
+    assert!(frame.len() < 16 << 10);
+    assert!(matches!(
+        MESSAGES_DICT.decompress(&frame, 1 << 20),
+        Err(DecompressError::SizeLimitExceeded { .. })
+    ));
+    assert_eq!(MESSAGES_DICT.decompress(&frame, 4 << 20).unwrap(), data);
+
+    frame.extend_from_slice(&[0; 3]);
+    assert!(matches!(
+        MESSAGES_DICT.decompress(&frame, 4 << 20),
+        Err(DecompressError::TrailingBytes { len: 3 })
+    ));
+}
+
+#[cfg(feature = "compress")]
+#[test]
+fn compresses_with_dictionary() {
+    for (_, file) in include_zstd_dir!("fixtures/messages", 19).iter() {
+        let message = file.decompress().unwrap();
+        let frame = MESSAGES_DICT.compress(&message, 19).unwrap();
+        assert!(frame.len() < zstd::bulk::compress(&message, 19).unwrap().len());
+        assert_eq!(MESSAGES_DICT.decompress(&frame, 1024).unwrap(), message);
+    }
+}
+
+#[test]
+fn rejects_text_decompressed_with_another_dictionary() {
+    // The messages dictionary with its content overwritten, which decodes the text into invalid UTF-8
+    const IMPOSTOR_DICT: EmbeddedZstdDict = include_zstd_dictionary!("fixtures/impostor.dict");
+    assert_eq!(IMPOSTOR_DICT.id(), MESSAGES_DICT.id());
+
+    let text = include_zstd_str!(
+        "fixtures/messages/02.json",
+        19,
+        dict = "fixtures/messages.dict"
+    );
+    assert!(matches!(
+        text.decompress_with_dict(&IMPOSTOR_DICT),
+        Err(DecompressError::Utf8(_))
+    ));
+}
@@ -0,0 +1,15 @@
+use include_zstd::include_zstd_dir;
+
+#[test]
+fn filters_directory_entries() {
+    let dir = include_zstd_dir!(
+        "fixtures/assets",
+        3,
+        include = ["**/*.json", "**/*.map"],
+        exclude = ["**/*.map"],
+        gitignore = true,
+    );
+
+    let paths: Vec<&str> = dir.iter().map(|(path, _)| path).collect();
+    assert_eq!(paths, ["a.json", "nested/b.json", "nested/deeper/c.json"]);
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "std")]
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    io::{self, Write},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use include_zstd::EmbeddedZstdSlice;
+
+/// Allocator keeping track of the peak number of bytes allocated at once.
+struct PeakAlloc {
+    current: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let current = self.current.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+        self.peak.fetch_max(current, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.current.fetch_sub(layout.size(), Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc {
+    current: AtomicUsize::new(0),
+    peak: AtomicUsize::new(0),
+};
+
+/// Decoder state that does not depend on the window, such as the entropy tables and the `BufReader` buffer.
+const OVERHEAD: usize = 64 << 10;
+
+/// Compresses `content` like `include_zstd!` does, with its size pledged and an optional `window_log`.
+fn compress(content: &[u8], window_log: Option<u32>) -> Vec<u8> {
+    let mut encoder = zstd::Encoder::new(Vec::new(), 19).unwrap();
+    encoder
+        .set_pledged_src_size(Some(content.len() as u64))
+        .unwrap();
+    if let Some(window_log) = window_log {
+        encoder.window_log(window_log).unwrap();
+    }
+    encoder.write_all(content).unwrap();
+    encoder.finish().unwrap()
+}
+
+/// Returns the peak number of bytes allocated by `f`, on top of what was allocated before.
+fn peak(f: impl FnOnce()) -> usize {
+    let before = ALLOC.current.load(Ordering::SeqCst);
+    ALLOC.peak.store(before, Ordering::SeqCst);
+
+    f();
+    ALLOC.peak.load(Ordering::SeqCst) - before
+}
+
+/// Returns the peak number of bytes allocated by reading `frame` to the end and by decompressing it into a buffer.
+fn peaks(frame: &[u8], content_size: usize) -> [usize; 2] {
+    let slice = unsafe { EmbeddedZstdSlice::new_unchecked(frame, content_size, &[0; 32]) };
+    let mut out = vec![0; content_size];
+
+    [
+        peak(|| {
+            let read = io::copy(&mut slice.reader().unwrap(), &mut io::sink()).unwrap();
+            assert_eq!(read, content_size as u64);
+        }),
+        peak(|| assert_eq!(slice.decompress_into(&mut out).unwrap(), content_size)),
+    ]
+}
+
+// A single test, as the allocator is shared by every thread of the test binary
+#[test]
+fn memory_is_bounded_by_window() {
+    let content: Vec<u8> = (0..2u32 << 20)
+        .map(|i| b'a' + (i.wrapping_mul(2654435761) >> 13) as u8 % 16)
+        .collect();
+
+    // Without `window_log`, the frame is a single segment and its whole content is buffered, even when decompressing
+    // into a caller's buffer
+    for peak in peaks(&compress(&content, None), content.len()) {
+        assert!(peak >= content.len(), "{peak}");
+        assert!(peak <= 4 * content.len() + OVERHEAD, "{peak}");
+    }
+
+    // A smaller window bounds memory usage regardless of the decompressed size
+    for window_log in [10, 14, 17] {
+        for peak in peaks(&compress(&content, Some(window_log)), content.len()) {
+            assert!(
+                peak <= 4 * (1 << window_log) + OVERHEAD,
+                "{window_log}: {peak}"
+            );
+        }
+    }
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use std::{env, path::Path, process::Command};
+
+/// Bare-metal target without `std`, where linking `std` fails instead of going unnoticed as it would on the host.
+const TARGET: &str = "thumbv7em-none-eabihf";
+
+#[test]
+fn builds_without_std() {
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| String::from("rustc"));
+    let sysroot = Command::new(rustc)
+        .args(["--print", "sysroot"])
+        .output()
+        .unwrap()
+        .stdout;
+    let sysroot = String::from_utf8(sysroot).unwrap();
+    if !Path::new(sysroot.trim())
+        .join("lib/rustlib")
+        .join(TARGET)
+        .exists()
+    {
+        eprintln!("skipping, the `{TARGET}` target is not installed");
+        return;
+    }
+
+    let dir = common::fixture(
+        "no-std-fixture",
+        ", default-features = false",
+        &[
+            ("asset.txt", "no_std"),
+            (
+                "src/lib.rs",
+                r#"#![no_std]
+
+extern crate alloc;
+
+use alloc::{boxed::Box, vec::Vec};
+
+pub fn decompress() -> Vec<u8> {
+    include_zstd::include_zstd!("asset.txt", 3).decompress().unwrap()
+}
+
+pub fn decompress_into(out: &mut [u8]) -> usize {
+    include_zstd::include_zstd!("asset.txt", 3).decompress_into(out).unwrap()
+}
+
+pub fn decompress_array() -> [u8; 6] {
+    include_zstd::include_zstd!("asset.txt", 3).decompress_array().unwrap()
+}
+
+pub fn decompress_boxed() -> Box<[u8]> {
+    include_zstd::include_zstd!("asset.txt", 3).into()
+}
+"#,
+            ),
+        ],
+    );
+
+    common::cargo(&dir, &["build", "--quiet", "--target", TARGET], &[]);
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use std::path::Path;
+
+fn run_fixture(dir: &Path) -> String {
+    String::from_utf8(common::cargo(dir, &["run", "--quiet"], &[]).stdout).unwrap()
+}
+
+#[test]
+fn rebuilds_when_included_file_changes() {
+    let dir = common::fixture(
+        "rebuild-fixture",
+        "",
+        &[(
+            "src/main.rs",
+            r#"fn main() {
+    let data: Vec<u8> = include_zstd::include_zstd!("asset.txt", 3).into();
+    print!("{}", String::from_utf8(data).unwrap());
+}
+"#,
+        )],
+    );
+
+    common::write(&dir, "asset.txt", "before");
+    assert_eq!(run_fixture(&dir), "before");
+
+    common::write(&dir, "asset.txt", "after");
+    assert_eq!(run_fixture(&dir), "after");
+}
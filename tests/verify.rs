@@ -0,0 +1,50 @@
+#![cfg(feature = "verify")]
+
+use include_zstd::{
+    include_zstd, include_zstd_dictionary, include_zstd_dir, DecompressError, EmbeddedZstdDict,
+    EmbeddedZstdSlice,
+};
+
+const CONTENT: &[u8] = include_bytes!("../data/udhr_en.txt");
+
+#[test]
+fn verifies_content_hash() {
+    let compressed_data = include_zstd!("data/udhr_en.txt", 19);
+    compressed_data.verify().unwrap();
+    for (_, file) in include_zstd_dir!("data", 19).iter() {
+        file.verify().unwrap();
+    }
+
+    // A frame paired with the hash of other content stands in for embedded bytes that were altered
+    let frame = zstd::encode_all(&b"altered"[..], 3).unwrap();
+    let content_hash = compressed_data.content_hash();
+    let altered = unsafe { EmbeddedZstdSlice::new_unchecked(&frame, 7, &content_hash) };
+
+    assert!(matches!(
+        altered.verify(),
+        Err(DecompressError::ContentHashMismatch { expected, .. }) if expected == content_hash
+    ));
+    assert_eq!(compressed_data.decompress().unwrap(), CONTENT);
+}
+
+#[test]
+fn verifies_content_hash_with_dictionary() {
+    const MESSAGES_DICT: EmbeddedZstdDict = include_zstd_dictionary!("fixtures/messages.dict");
+    const IMPOSTOR_DICT: EmbeddedZstdDict = include_zstd_dictionary!("fixtures/impostor.dict");
+
+    let message = include_zstd!(
+        "fixtures/messages/08.json",
+        19,
+        dict = "fixtures/messages.dict"
+    );
+    message.verify_with_dict(&MESSAGES_DICT).unwrap();
+    assert!(matches!(
+        message.verify(),
+        Err(DecompressError::DictionaryRequired { .. })
+    ));
+    // A dictionary reusing the ID decodes other content
+    assert!(matches!(
+        message.verify_with_dict(&IMPOSTOR_DICT),
+        Err(DecompressError::ContentHashMismatch { .. })
+    ));
+}
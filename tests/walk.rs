@@ -0,0 +1,87 @@
+mod common;
+
+use std::fs;
+
+#[test]
+fn skips_target_directory_and_hidden_samples() {
+    let dir = common::fixture(
+        "walk-fixture",
+        "",
+        &[
+            (
+                "src/main.rs",
+                r#"use include_zstd::{include_zstd_dictionary, include_zstd_dir, EmbeddedZstdDict};
+
+const DICT: EmbeddedZstdDict = include_zstd_dictionary!(samples = "**/*.json", size = 1024);
+
+fn main() {
+    println!("{}", DICT.id());
+    for (path, _) in include_zstd_dir!(".", 3).iter() {
+        println!("{path}");
+    }
+}
+"#,
+            ),
+            (".hidden/skipped.json", "{}"),
+        ],
+    );
+    fs::create_dir_all(dir.join("samples")).unwrap();
+    for entry in fs::read_dir("fixtures/messages").unwrap() {
+        let path = entry.unwrap().path();
+        fs::copy(&path, dir.join("samples").join(path.file_name().unwrap())).unwrap();
+    }
+
+    // The target directory lies within the crate, where the walks would otherwise reach it
+    let args = ["run", "--quiet", "--target-dir", "target"];
+    let output = String::from_utf8(common::cargo(&dir, &args, &[]).stdout).unwrap();
+    let (dict_id, paths) = output.split_once('\n').unwrap();
+    let paths: Vec<&str> = paths.lines().collect();
+    assert!(paths.contains(&"samples/00.json"));
+    assert!(paths.contains(&"src/main.rs"));
+    assert!(paths.contains(&".hidden/skipped.json"));
+    assert!(
+        paths.iter().all(|path| !path.starts_with("target/")),
+        "{paths:?}"
+    );
+
+    // Hidden directories are kept in the directory, but not used as samples, so the dictionary stays the same
+    common::write(
+        &dir,
+        ".hidden/skipped.json",
+        &"{\"hidden\": true}".repeat(100),
+    );
+    let output = String::from_utf8(common::cargo(&dir, &args, &[]).stdout).unwrap();
+    assert_eq!(output.split_once('\n').unwrap().0, dict_id);
+
+    // Nothing the build writes is tracked, so building again does not rebuild the crate
+    let output = common::cargo(&dir, &["build", "--target-dir", "target"], &[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.contains("Compiling walk-fixture"), "{stderr}");
+}
+
+#[test]
+fn keeps_files_next_to_nested_target_directory() {
+    let dir = common::fixture(
+        "walk-nested-target-fixture",
+        "",
+        &[
+            (
+                "src/main.rs",
+                r#"fn main() {
+    for (path, _) in include_zstd::include_zstd_dir!("web", 1).iter() {
+        println!("{path}");
+    }
+}
+"#,
+            ),
+            ("web/index.html", "<html></html>"),
+            ("web/build/assets/app.js", "main();"),
+        ],
+    );
+
+    // `web/build` holds the target directory, but is not the target directory itself
+    let args = ["run", "--quiet", "--target-dir", "web/build/target"];
+    let output = String::from_utf8(common::cargo(&dir, &args, &[]).stdout).unwrap();
+    let paths: Vec<&str> = output.lines().collect();
+    assert_eq!(paths, ["build/assets/app.js", "index.html"]);
+}
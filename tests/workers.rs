@@ -0,0 +1,48 @@
+mod common;
+
+use std::{fmt::Write, fs};
+
+#[test]
+fn multithreaded_output_is_deterministic() {
+    let dir = common::fixture(
+        "workers-fixture",
+        "",
+        &[(
+            "src/main.rs",
+            r#"fn main() {
+    // The compressed data is held by value, which needs more than the main thread's stack
+    let compare = || {
+        let one = include_zstd::include_zstd!("asset.txt", 1, workers = 1);
+        let four = include_zstd::include_zstd!("asset.txt", 1, workers = 4);
+        let single = include_zstd::include_zstd!("asset.txt", 1);
+        assert!(one == four);
+        assert_eq!(four.decompress().unwrap(), single.decompress().unwrap());
+        four.size()
+    };
+    let size = std::thread::Builder::new().stack_size(64 << 20).spawn(compare).unwrap().join().unwrap();
+    print!("{size}");
+}
+"#,
+        )],
+    );
+    // Large enough to be split into several jobs, which are compressed in parallel
+    let mut asset = String::new();
+    for i in 0..1_000_000_u64 {
+        writeln!(asset, "{} {}", i * 7919 % 104_729, i % 97).unwrap();
+    }
+    fs::write(dir.join("asset.txt"), asset).unwrap();
+
+    let build = || {
+        let output = common::cargo(&dir, &["run", "--quiet"], &[("INCLUDE_ZSTD_CACHE_DIR", "")]);
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let first = build();
+
+    // Rebuilding compresses everything again, without the cache, and must produce the same bytes
+    common::write(
+        &dir,
+        "src/main.rs",
+        &fs::read_to_string(dir.join("src/main.rs")).unwrap(),
+    );
+    assert_eq!(build(), first);
+}